@@ -0,0 +1,83 @@
+// Timings for the hot paths of a run: parsing a full list page, merging
+// several pages and composing the feed. Run with `cargo bench`.
+
+use dgmd_notice_rss::{
+    compose_xml, dedup_notices, merge_page, parse_html, sort_notices, Locale, Meta, Notice,
+    RssItemOptions,
+};
+use fixtures::list_page;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[path = "../src/tests/fixtures.rs"]
+mod fixtures;
+
+const BASE_URL: &str = "http://media.ajou.ac.kr/media/board/notice.do";
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    // Warm up, then run for a fixed wall-clock budget.
+    for _ in 0..10 {
+        black_box(f());
+    }
+    let budget = Duration::from_secs(2);
+    let started = Instant::now();
+    let mut iterations = 0u32;
+    while started.elapsed() < budget {
+        black_box(f());
+        iterations += 1;
+    }
+    let per_iter = started.elapsed() / iterations;
+    println!(
+        "{:<24} {:>12?}/iter ({} iterations)",
+        name, per_iter, iterations
+    );
+}
+
+fn parse(html: &str) -> Vec<Notice> {
+    parse_html(html, BASE_URL, "(untitled)").0
+}
+
+fn main() {
+    let page = list_page(1221, 30);
+    let pages = (0..3)
+        .map(|page| parse(&list_page(1221 - page * 30, 30)))
+        .collect::<Vec<_>>();
+    let notices = parse(&page);
+    assert_eq!(notices.len(), 32);
+
+    let meta = Meta {
+        title: "Ajou University Department of Digital Media Notices",
+        home_page_url: "https://media.ajou.ac.kr/media/board/board01.jsp",
+        feed_url: "https://ajou-hack.github.io/dgmd-notice-rss/feed.json",
+    };
+    let item_options = RssItemOptions {
+        index_in_title: false,
+        guid_permalink: false,
+        full: false,
+        comments_base_url: None,
+        messages: Locale::default().messages(),
+    };
+    let author_emails = HashMap::new();
+
+    bench("parse_html (30 rows)", || parse(&page));
+    bench("merge (3 pages)", || {
+        let mut merged = Vec::new();
+        for (i, page) in pages.iter().enumerate() {
+            merge_page(&mut merged, page.clone(), i as u32, i as u32 * 30);
+        }
+        dedup_notices(&mut merged, false);
+        sort_notices(&mut merged);
+        merged
+    });
+    bench("compose_xml (32 items)", || {
+        compose_xml(
+            &notices,
+            &meta,
+            Some(meta.feed_url),
+            &author_emails,
+            Some("Mon, 03 Mar 2025 09:00:00 +0900"),
+            &item_options,
+        )
+    });
+}
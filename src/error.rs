@@ -0,0 +1,119 @@
+use std::io;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{url} returned {status}: {snippet}")]
+    Status {
+        url: String,
+        status: u16,
+        snippet: String,
+    },
+    #[error("failed to fetch offsets {0:?}")]
+    PartialFetch(Vec<u32>),
+    #[error("no numbered notices at offset {0}")]
+    NoNotices(u32),
+    #[error("parse error: {0}")]
+    Parse(String),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("config error: {0}")]
+    Config(String),
+    #[error("state error: {0}")]
+    State(String),
+    #[error("xlsx error: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+    #[error("output is {size} bytes, over the {limit} byte limit")]
+    OutputTooLarge { size: usize, limit: usize },
+    #[error("board layout changed from '{previous}' to '{current}'; check the selectors")]
+    LayoutChanged { previous: String, current: String },
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Http(_) => 2,
+            Error::PartialFetch(_) => 3,
+            Error::Parse(_) => 4,
+            Error::Io(_) => 5,
+            Error::Config(_) => 6,
+            Error::State(_) => 7,
+            Error::Status { .. } => 8,
+            Error::Xlsx(_) => 9,
+            Error::NoNotices(_) => 10,
+            Error::OutputTooLarge { .. } => 11,
+            Error::LayoutChanged { .. } => 12,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn io_error() -> Error {
+        io::Error::new(io::ErrorKind::PermissionDenied, "rss.xml is read-only").into()
+    }
+
+    #[test]
+    fn display_names_the_error_kind() {
+        assert_eq!(
+            Error::Parse("missing b-num-box".to_string()).to_string(),
+            "parse error: missing b-num-box"
+        );
+        assert_eq!(
+            Error::Config("unknown mode 'yaml'".to_string()).to_string(),
+            "config error: unknown mode 'yaml'"
+        );
+        assert_eq!(
+            Error::State("last_index is not a number".to_string()).to_string(),
+            "state error: last_index is not a number"
+        );
+        assert_eq!(io_error().to_string(), "io error: rss.xml is read-only");
+        assert_eq!(
+            Error::Status {
+                url: "http://media.ajou.ac.kr/media/board/notice.do".to_string(),
+                status: 503,
+                snippet: "점검 중".to_string(),
+            }
+            .to_string(),
+            "http://media.ajou.ac.kr/media/board/notice.do returned 503: 점검 중"
+        );
+    }
+
+    #[test]
+    fn io_errors_convert_and_keep_their_source() {
+        let err = io_error();
+        assert!(matches!(err, Error::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn every_kind_has_its_own_exit_code() {
+        let codes = [
+            Error::PartialFetch(vec![30]).exit_code(),
+            Error::Parse(String::new()).exit_code(),
+            io_error().exit_code(),
+            Error::Config(String::new()).exit_code(),
+            Error::State(String::new()).exit_code(),
+            Error::Status {
+                url: String::new(),
+                status: 500,
+                snippet: String::new(),
+            }
+            .exit_code(),
+            Error::NoNotices(0).exit_code(),
+            Error::OutputTooLarge { size: 2, limit: 1 }.exit_code(),
+            Error::LayoutChanged {
+                previous: String::new(),
+                current: String::new(),
+            }
+            .exit_code(),
+        ];
+        assert_eq!(codes, [3, 4, 5, 6, 7, 8, 10, 11, 12]);
+    }
+}
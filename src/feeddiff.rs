@@ -0,0 +1,126 @@
+use htmlescape::decode_html;
+use std::collections::BTreeMap;
+
+struct Item {
+    guid: String,
+    title: String,
+    link: String,
+    description: String,
+}
+
+fn element(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}", tag))?;
+    let content = start + xml[start..].find('>')? + 1;
+    let end = content + xml[content..].find(&format!("</{}>", tag))?;
+    let text = xml[content..end].trim();
+
+    Some(decode_html(text).unwrap_or_else(|_| text.to_string()))
+}
+
+fn parse_items(xml: &str) -> BTreeMap<String, Item> {
+    xml.split("<item>")
+        .skip(1)
+        .filter_map(|item| item.split("</item>").next())
+        .map(|item| Item {
+            guid: element(item, "guid").unwrap_or_default(),
+            title: element(item, "title").unwrap_or_default(),
+            link: element(item, "link").unwrap_or_default(),
+            description: element(item, "description").unwrap_or_default(),
+        })
+        // The guid survives a rewritten link; older feeds without one fall
+        // back to the link.
+        .map(|item| {
+            let key = if item.guid.is_empty() {
+                item.link.clone()
+            } else {
+                item.guid.clone()
+            };
+            (key, item)
+        })
+        .collect()
+}
+
+pub fn diff_feeds(old: &str, new: &str) -> String {
+    let old = parse_items(old);
+    let new = parse_items(new);
+
+    let added = new
+        .iter()
+        .filter(|(key, _)| !old.contains_key(*key))
+        .map(|(_, item)| format!("+ {} ({})", item.title, item.link))
+        .collect::<Vec<_>>();
+    let removed = old
+        .iter()
+        .filter(|(key, _)| !new.contains_key(*key))
+        .map(|(_, item)| format!("- {} ({})", item.title, item.link))
+        .collect::<Vec<_>>();
+    let changed = new
+        .iter()
+        .filter_map(|(key, item)| Some((old.get(key)?, item)))
+        .filter(|(old, new)| {
+            old.title != new.title || old.link != new.link || old.description != new.description
+        })
+        .map(|(_, item)| format!("~ {} ({})", item.title, item.link))
+        .collect::<Vec<_>>();
+
+    format!(
+        "added: {}, removed: {}, changed: {}\n{}",
+        added.len(),
+        removed.len(),
+        changed.len(),
+        [added, removed, changed].concat().join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = include_str!("../tests/fixtures/feed_old.xml");
+    const NEW: &str = include_str!("../tests/fixtures/feed_new.xml");
+
+    #[test]
+    fn diff_lists_added_removed_and_changed_items() {
+        let base = "http://media.ajou.ac.kr/media/board/notice.do?mode=view&articleNo=";
+        assert_eq!(
+            diff_feeds(OLD, NEW),
+            format!(
+                "added: 1, removed: 1, changed: 1\n\
+                 + 2025 디지털미디어 졸업작품전시회 개최 ({base}339950)\n\
+                 - 학과 사물함 배정 결과 안내 ({base}339777)\n\
+                 ~ 디지털미디어학과 Career Consultant 취업/진로상담 ({base}339999)",
+                base = base
+            )
+        );
+    }
+
+    #[test]
+    fn items_are_matched_by_guid_before_link() {
+        let feed = |link: &str| {
+            format!(
+                "<item><title>공지</title><link>{}</link>\
+                 <guid isPermaLink=\"false\">340001</guid></item>",
+                link
+            )
+        };
+        assert_eq!(
+            diff_feeds(
+                &feed("http://old/?articleNo=340001"),
+                &feed("http://new/?articleNo=340001")
+            ),
+            "added: 0, removed: 0, changed: 1\n~ 공지 (http://new/?articleNo=340001)"
+        );
+
+        // Without a guid the link is all there is to go by.
+        let feed = |link: &str| format!("<item><title>공지</title><link>{}</link></item>", link);
+        assert_eq!(
+            diff_feeds(&feed("http://old/"), &feed("http://new/")),
+            "added: 1, removed: 1, changed: 0\n+ 공지 (http://new/)\n- 공지 (http://old/)"
+        );
+    }
+
+    #[test]
+    fn identical_feeds_have_no_differences() {
+        assert_eq!(diff_feeds(OLD, OLD), "added: 0, removed: 0, changed: 0\n");
+    }
+}
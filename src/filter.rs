@@ -0,0 +1,332 @@
+use crate::Notice;
+use std::str::FromStr;
+
+// A small predicate language for --filter-expr:
+//
+//   expr  := and ("||" and)*
+//   and   := unary ("&&" unary)*
+//   unary := "!" unary | "(" expr ")" | flag | field op string
+//   field := title | category | author | link | expired_at | body
+//   flag  := is_new | is_important | has_attachment | updated | pinned
+//   op    := "==" | "!=" | contains
+//
+// e.g. category == "장학" && title contains "신청"
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Category,
+    Author,
+    Link,
+    ExpiredAt,
+    Body,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flag {
+    IsNew,
+    IsImportant,
+    HasAttachment,
+    Updated,
+    Pinned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Contains,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Flag(Flag),
+    Compare(Field, Op, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug)]
+pub struct Filter(Expr);
+
+impl Filter {
+    pub fn matches(&self, notice: &Notice) -> bool {
+        eval(&self.0, notice)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expr()?;
+        match parser.tokens.get(parser.pos) {
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Ok(Filter(expr)),
+        }
+    }
+}
+
+fn eval(expr: &Expr, notice: &Notice) -> bool {
+    match expr {
+        Expr::Or(lhs, rhs) => eval(lhs, notice) || eval(rhs, notice),
+        Expr::And(lhs, rhs) => eval(lhs, notice) && eval(rhs, notice),
+        Expr::Not(expr) => !eval(expr, notice),
+        Expr::Flag(flag) => match flag {
+            Flag::IsNew => notice.is_new,
+            Flag::IsImportant => notice.is_important,
+            Flag::HasAttachment => notice.has_attachment,
+            Flag::Updated => notice.updated,
+            Flag::Pinned => notice.index == -1,
+        },
+        Expr::Compare(field, op, value) => {
+            let text = match field {
+                Field::Title => notice.title.as_str(),
+                Field::Category => notice.category.as_str(),
+                Field::Author => notice.author.as_str(),
+                Field::Link => notice.link.as_str(),
+                Field::ExpiredAt => notice.expired_at.as_str(),
+                Field::Body => notice.body.as_deref().unwrap_or(""),
+            };
+            match op {
+                Op::Eq => text.trim() == value,
+                Op::Ne => text.trim() != value,
+                Op::Contains => text.contains(value.as_str()),
+            }
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' {
+                    Token::LParen
+                } else {
+                    Token::RParen
+                });
+            }
+            '&' | '|' | '=' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    return Err(format!("expected '{}{}'", c, c));
+                }
+                tokens.push(match c {
+                    '&' => Token::And,
+                    '|' => Token::Or,
+                    _ => Token::Eq,
+                });
+            }
+            '!' => {
+                chars.next();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push(Token::Ne);
+                } else {
+                    tokens.push(Token::Not);
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => return Err("unterminated string".to_string()),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.pos) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        while self.eat(&Token::Or) {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while self.eat(&Token::And) {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::LParen) => {
+                let expr = self.expr()?;
+                if !self.eat(&Token::RParen) {
+                    return Err("expected ')'".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) => {
+                if let Some(flag) = flag(&ident) {
+                    return Ok(Expr::Flag(flag));
+                }
+                let field = field(&ident).ok_or_else(|| format!("unknown field '{}'", ident))?;
+                let op = match self.next() {
+                    Some(Token::Eq) => Op::Eq,
+                    Some(Token::Ne) => Op::Ne,
+                    Some(Token::Ident(op)) if op == "contains" => Op::Contains,
+                    _ => return Err(format!("expected ==, != or contains after '{}'", ident)),
+                };
+                match self.next() {
+                    Some(Token::Str(value)) => Ok(Expr::Compare(field, op, value)),
+                    _ => Err(format!("expected a quoted string after '{}'", ident)),
+                }
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn field(name: &str) -> Option<Field> {
+    match name {
+        "title" => Some(Field::Title),
+        "category" => Some(Field::Category),
+        "author" => Some(Field::Author),
+        "link" => Some(Field::Link),
+        "expired_at" => Some(Field::ExpiredAt),
+        "body" => Some(Field::Body),
+        _ => None,
+    }
+}
+
+fn flag(name: &str) -> Option<Flag> {
+    match name {
+        "is_new" => Some(Flag::IsNew),
+        "is_important" => Some(Flag::IsImportant),
+        "has_attachment" => Some(Flag::HasAttachment),
+        "updated" => Some(Flag::Updated),
+        "pinned" => Some(Flag::Pinned),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample_notices;
+
+    fn matching(expr: &str) -> Vec<String> {
+        let filter = expr.parse::<Filter>().unwrap();
+        sample_notices()
+            .into_iter()
+            .filter(|notice| filter.matches(notice))
+            .map(|notice| notice.title)
+            .collect()
+    }
+
+    #[test]
+    fn comparisons_and_contains() {
+        assert_eq!(
+            matching(r#"category == "장학""#),
+            ["2025학년도 1학기 교내장학 신청 안내 & 서류 제출"]
+        );
+        assert_eq!(matching(r#"title contains "안내""#).len(), 3);
+        assert_eq!(matching(r#"category != "장학""#).len(), 4);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            matching(r#"category == "장학" && title contains "신청" || category == "행사""#),
+            [
+                "2025학년도 1학기 교내장학 신청 안내 & 서류 제출",
+                "2025 디지털미디어 졸업작품전시회 개최",
+            ]
+        );
+        assert!(matching(r#"category == "장학" && (title contains "없음" || pinned)"#).is_empty());
+        assert_eq!(matching("pinned && !is_important").len(), 0);
+        assert_eq!(matching("!pinned").len(), 3);
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        let error = |expr: &str| expr.parse::<Filter>().err().unwrap();
+
+        assert_eq!(error(r#"views == "3""#), "unknown field 'views'");
+        assert_eq!(error(r#"title = "a""#), "expected '=='");
+        assert_eq!(error(r#"title contains "a"#), "unterminated string");
+        assert_eq!(error("(pinned"), "expected ')'");
+        assert_eq!(error("pinned pinned"), r#"unexpected Ident("pinned")"#);
+        assert_eq!(error(""), "unexpected end of expression");
+
+        // ... and stop the run before anything is fetched.
+        let err = crate::tests::options_error(&["--filter-expr", "views > 3"]);
+        assert_eq!(err.exit_code(), 6);
+    }
+}
@@ -0,0 +1,3336 @@
+mod error;
+mod feeddiff;
+mod filter;
+mod locale;
+mod romanize;
+mod state;
+#[cfg(test)]
+mod tests;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat, TimeZone, Utc};
+use encoding_rs::{Encoding, UTF_8};
+pub use error::{Error, Result};
+use feeddiff::diff_feeds;
+use filter::Filter;
+use htmlescape::{decode_html, encode_minimal};
+pub use locale::{Locale, Messages};
+use log::{debug, info, warn, LevelFilter};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use reqwest::blocking::RequestBuilder;
+use reqwest::header::{CONTENT_TYPE, LAST_MODIFIED};
+use reqwest::{Method, Url};
+use romanize::romanize;
+use rust_xlsxwriter::{Format, Workbook};
+use schemars::JsonSchema;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use state::{load_state, save_state, BoardState, State, DEFAULT_DEDUP_WINDOW, DEFAULT_STATE_PATH};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io::{self, Write};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+const OFFSET: u32 = 0;
+const LIMIT: u8 = 30;
+
+const STATUS_SNIPPET_LEN: usize = 200;
+/// Retries never wait longer than this, however many attempts came before.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+const TITLE_SELECTORS: [&str; 4] = [
+    "td.b-td-left > div.b-title-box > a",
+    "div.b-title-box a",
+    "td.b-td-left a",
+    "td a[href*=\"articleNo\"]",
+];
+
+const CARD_TITLE_SELECTORS: [&str; 2] = [".b-title-box > a", "a[href*=\"articleNo\"]"];
+
+const TABLE_SELECTOR: &str = "table.board-table";
+const CARD_SELECTOR: &str = ".b-card-list .b-card";
+
+struct RowSelectors {
+    index: &'static str,
+    category: &'static str,
+    author: &'static str,
+    expired_at: &'static str,
+    titles: &'static [&'static str],
+}
+
+const TABLE_ROW: RowSelectors = RowSelectors {
+    index: "td.b-num-box",
+    category: "td.b-num-box + td",
+    author: "td.b-no-right + td",
+    expired_at: "td.b-no-right + td + td",
+    titles: &TITLE_SELECTORS,
+};
+
+const CARD_ROW: RowSelectors = RowSelectors {
+    index: ".b-num-box",
+    category: ".b-cate",
+    author: ".b-writer",
+    expired_at: ".b-date",
+    titles: &CARD_TITLE_SELECTORS,
+};
+
+const BOARD_ID: &str = "dgmd";
+const BASE_URL: &str = "http://media.ajou.ac.kr/media/board/notice.do";
+const META: Meta = Meta {
+    title: "Ajou University Department of Digital Media Notices",
+    home_page_url: "https://media.ajou.ac.kr/media/board/board01.jsp",
+    feed_url: "https://ajou-hack.github.io/dgmd-notice-rss/feed.json",
+};
+
+pub struct Meta<'a> {
+    pub title: &'a str,
+    pub home_page_url: &'a str,
+    pub feed_url: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Config {
+    boards: Vec<Board>,
+}
+
+#[derive(Deserialize)]
+struct Board {
+    id: String,
+    base_url: String,
+    title: String,
+    home_page_url: String,
+    feed_url: String,
+    /// Caps this board's feed unless --max-items is given.
+    #[serde(default)]
+    max_items: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'a str,
+    title: &'a str,
+    home_page_url: &'a str,
+    feed_url: &'a str,
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+    id: String,
+    url: String,
+    title: String,
+    content_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_html: Option<String>,
+    #[serde(rename = "_expired_at")]
+    expired_at: String,
+    #[serde(rename = "_iso_expired_at", skip_serializing_if = "Option::is_none")]
+    iso_expired_at: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "Notice")]
+struct JsonNotice {
+    index: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    article_no: Option<String>,
+    title: String,
+    author: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    department: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author_name: Option<String>,
+    category: String,
+    link: String,
+    expired_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iso_expired_at: Option<String>,
+    is_new: bool,
+    is_important: bool,
+    has_attachment: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    updated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_links: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExtraNotice {
+    title: String,
+    link: String,
+    #[serde(default)]
+    category: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    expired_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notice {
+    index: i32,
+    title: String,
+    author: String,
+    category: String,
+    link: String,
+    expired_at: String,
+    is_new: bool,
+    is_important: bool,
+    has_attachment: bool,
+    body: Option<String>,
+    article_no: Option<String>,
+    updated: bool,
+    first_seen: Option<String>,
+    related_links: Vec<String>,
+    raw_html: Option<String>,
+    priority: Option<u32>,
+    source_offset: Option<u32>,
+    page: Option<u32>,
+    /// The content hash taken from the row as scraped, so that decoding,
+    /// normalizing or remapping authors later doesn't read as an edit.
+    scraped_hash: Option<String>,
+}
+
+impl Notice {
+    /// The notice's page on the board under `base_url`, or its own link
+    /// when it has no article number.
+    pub fn view_url(&self, base_url: &str) -> String {
+        match (&self.article_no, Url::parse(base_url)) {
+            (Some(article_no), Ok(mut url)) => {
+                url.query_pairs_mut()
+                    .append_pair("mode", "view")
+                    .append_pair("articleNo", article_no);
+                url.to_string()
+            }
+            (Some(article_no), Err(_)) => {
+                format!("{}?mode=view&articleNo={}", base_url, article_no)
+            }
+            (None, _) => self.link.clone(),
+        }
+    }
+}
+
+/// Sent with every request; kept out of `Debug` output.
+#[derive(Default, Clone)]
+pub struct Credentials {
+    /// `name=value` pairs for the Cookie header.
+    pub cookies: Vec<String>,
+    /// `user:password` for HTTP basic auth.
+    pub basic_auth: Option<String>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("cookies", &format!("<{} redacted>", self.cookies.len()))
+            .field(
+                "basic_auth",
+                &self.basic_auth.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// How the board is fetched. `Default` matches the CLI's defaults.
+#[derive(Clone)]
+pub struct FetchConfig {
+    /// Per-request timeout; `None` waits forever.
+    pub timeout: Option<Duration>,
+    /// Retries for connection errors, 429 and 5xx responses.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each one after it.
+    pub backoff_base: Duration,
+    pub user_agent: String,
+    /// Accept invalid TLS certificates, which the board has been known to serve.
+    pub insecure: bool,
+    pub proxy: Option<String>,
+    pub credentials: Credentials,
+    /// Retries for a list page that comes back with an empty table.
+    pub empty_retries: u32,
+    pub http_version: HttpVersion,
+    /// Retry an empty search without its search parameters.
+    pub search_fallback: bool,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        FetchConfig {
+            timeout: Some(Duration::from_secs(30)),
+            retries: 0,
+            backoff_base: Duration::from_millis(500),
+            user_agent: user_agent(),
+            insecure: true,
+            proxy: None,
+            credentials: Credentials::default(),
+            empty_retries: 0,
+            http_version: HttpVersion::Auto,
+            search_fallback: false,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+enum Layout {
+    Table,
+    Cards,
+    #[default]
+    Auto,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(layout: &str) -> std::result::Result<Self, Self::Err> {
+        match layout {
+            "table" => Ok(Layout::Table),
+            "cards" => Ok(Layout::Cards),
+            "auto" => Ok(Layout::Auto),
+            _ => Err("expected table, cards or auto".to_string()),
+        }
+    }
+}
+
+struct ParseConfig<'a> {
+    layout: Layout,
+    untitled: &'a str,
+    /// Fewer rows than this on the first page is treated like an error page.
+    min_rows: usize,
+}
+
+#[derive(Default, Clone, Copy)]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    Http1,
+    Http2,
+}
+
+impl FromStr for HttpVersion {
+    type Err = String;
+
+    fn from_str(version: &str) -> std::result::Result<Self, Self::Err> {
+        match version {
+            "auto" => Ok(HttpVersion::Auto),
+            "1.1" => Ok(HttpVersion::Http1),
+            "2" => Ok(HttpVersion::Http2),
+            _ => Err("expected 1.1, 2 or auto".to_string()),
+        }
+    }
+}
+
+/// RSS wants RFC 2822 dates; JSON, JSON Feed and Dublin Core want RFC 3339.
+#[derive(Clone, Copy)]
+enum DateFormat {
+    Rss,
+    Iso,
+}
+
+#[derive(Default, Clone, Copy)]
+enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+#[derive(Default)]
+struct Options {
+    last_index: Option<i32>,
+    mode: String,
+    mode_args: Vec<String>,
+    no_pinned_marker: bool,
+    pages: u32,
+    author_emails: HashMap<String, String>,
+    fail_on_empty: bool,
+    max_title_len: Option<usize>,
+    with_body: bool,
+    max_in_flight: usize,
+    config: String,
+    state: Option<String>,
+    output_dir: String,
+    top: Option<usize>,
+    max_items: Option<usize>,
+    fetch: FetchConfig,
+    relative_dates: bool,
+    stable_build_date: bool,
+    allow_partial: bool,
+    min_rows: usize,
+    dedup_window: usize,
+    filter_regex: Option<Regex>,
+    line_ending: LineEnding,
+    raw_body: bool,
+    category_emojis: HashMap<String, String>,
+    index_in_title: bool,
+    interval_secs: u64,
+    percent_decode: bool,
+    compact_json: bool,
+    wrap_width: usize,
+    guid_permalink: bool,
+    full: bool,
+    emit_first_seen: bool,
+    normalize: bool,
+    only_new: bool,
+    emit_comments: bool,
+    link_prefix: Option<String>,
+    link_replace: Option<(String, String)>,
+    strict: bool,
+    publish_gist: bool,
+    keep_pinned_duplicates: bool,
+    split_by_category: bool,
+    author_delimiter: Option<String>,
+    max_description_len: Option<usize>,
+    check_last_modified: bool,
+    output: Option<String>,
+    error_webhook: Option<String>,
+    emit_checksum: bool,
+    locale: Locale,
+    new_by_badge: bool,
+    layout: Layout,
+    extra_notices: Vec<Notice>,
+    feed_url: Option<String>,
+    human_url: Option<String>,
+    separate_pinned: bool,
+    max_output_bytes: Option<usize>,
+    max_output_error: bool,
+    include_raw_html: bool,
+    post_webhook: Option<String>,
+    author_map: HashMap<String, String>,
+    recent_runs: Option<u64>,
+    dedup_titles: bool,
+    quiet: bool,
+    filter_expr: Option<Filter>,
+    deterministic: bool,
+    preview_chars: Option<usize>,
+    romanize: bool,
+    abort_on_layout_change: bool,
+    base_url: String,
+}
+
+impl Options {
+    fn md_options(&self) -> MdOptions<'_> {
+        MdOptions {
+            pinned_marker: !self.no_pinned_marker,
+            max_title_len: self.max_title_len,
+            relative_dates: self.relative_dates,
+            emojis: &self.category_emojis,
+            index_in_title: self.index_in_title,
+            separate_pinned: self.separate_pinned,
+            messages: self.locale.messages(),
+        }
+    }
+
+    fn meta(&self) -> Meta<'_> {
+        Meta {
+            title: META.title,
+            home_page_url: self.human_url.as_deref().unwrap_or(META.home_page_url),
+            feed_url: self.feed_url.as_deref().unwrap_or(META.feed_url),
+        }
+    }
+
+    fn parse_config(&self) -> ParseConfig<'static> {
+        ParseConfig {
+            layout: self.layout,
+            untitled: self.locale.messages().untitled,
+            min_rows: self.min_rows,
+        }
+    }
+
+    fn rss_item_options<'a>(&self, base_url: &'a str) -> RssItemOptions<'a> {
+        RssItemOptions {
+            index_in_title: self.index_in_title,
+            guid_permalink: self.guid_permalink,
+            full: self.full || self.preview_chars.is_some(),
+            comments_base_url: self.emit_comments.then_some(base_url),
+            messages: self.locale.messages(),
+        }
+    }
+}
+
+struct MdOptions<'a> {
+    pinned_marker: bool,
+    max_title_len: Option<usize>,
+    relative_dates: bool,
+    emojis: &'a HashMap<String, String>,
+    index_in_title: bool,
+    separate_pinned: bool,
+    messages: &'static Messages,
+}
+
+pub struct RssItemOptions<'a> {
+    pub index_in_title: bool,
+    pub guid_permalink: bool,
+    pub full: bool,
+    pub comments_base_url: Option<&'a str>,
+    pub messages: &'static Messages,
+}
+
+#[derive(Debug)]
+enum MissingField {
+    Title,
+    Link,
+    Index,
+}
+
+impl fmt::Display for MissingField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MissingField::Title => write!(f, "missing title"),
+            MissingField::Link => write!(f, "missing link"),
+            MissingField::Index => write!(f, "missing index"),
+        }
+    }
+}
+
+fn parse_base_url(base_url: &str) -> Result<Url> {
+    Url::parse(base_url)
+        .map_err(|err| Error::Config(format!("invalid base url '{}': {}", base_url, err)))
+}
+
+fn list_url(base_url: &Url, limit: u8, offset: u32) -> Url {
+    const PARAMS: [&str; 3] = ["mode", "articleLimit", "article.offset"];
+
+    // Keep whatever query the base URL already carries, minus the paging
+    // parameters we set ourselves.
+    let existing = base_url
+        .query_pairs()
+        .filter(|(key, _)| !PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+
+    let mut url = base_url.clone();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(existing)
+        .append_pair("mode", "list")
+        .append_pair("articleLimit", &limit.to_string())
+        .append_pair("article.offset", &offset.to_string());
+    url
+}
+
+/// The list URL without the board's search parameters, or `None` if it
+/// isn't a search.
+fn without_search(base_url: &Url) -> Option<Url> {
+    const PARAMS: [&str; 2] = ["srSearchKey", "srSearchVal"];
+
+    if !base_url
+        .query_pairs()
+        .any(|(key, value)| key == "srSearchVal" && !value.is_empty())
+    {
+        return None;
+    }
+
+    let existing = base_url
+        .query_pairs()
+        .filter(|(key, _)| !PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    let mut url = base_url.clone();
+    url.query_pairs_mut().clear().extend_pairs(existing);
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+    Some(url)
+}
+
+/// One list page of the board at `base_url`, with the default fetch settings.
+pub fn fetch_html(base_url: &Url, limit: u8, offset: u32) -> Result<String> {
+    fetch_html_with(&FetchConfig::default(), base_url, limit, offset)
+}
+
+/// One list page of the board at `base_url`.
+pub fn fetch_html_with(
+    config: &FetchConfig,
+    base_url: &Url,
+    limit: u8,
+    offset: u32,
+) -> Result<String> {
+    fetch_page(list_url(base_url, limit, offset).as_str(), config)
+}
+
+fn is_retryable(err: &Error) -> bool {
+    match err {
+        Error::Http(_) => true,
+        Error::Status { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+/// `base` doubled for every attempt so far. A large --retries would overflow
+/// the multiplier, so both it and the result saturate at MAX_BACKOFF.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+    base.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+fn fetch_page(url: &str, config: &FetchConfig) -> Result<String> {
+    let mut attempt = 0;
+    loop {
+        match fetch_page_once(url, config) {
+            Err(err) if attempt < config.retries && is_retryable(&err) => {
+                let delay = backoff_delay(config.backoff_base, attempt);
+                warn!("retrying {} in {:?}: {}", url, delay, err);
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn request(method: Method, url: &str, config: &FetchConfig) -> Result<RequestBuilder> {
+    let mut client = reqwest::blocking::Client::builder()
+        .danger_accept_invalid_certs(config.insecure)
+        .timeout(config.timeout);
+    if let Some(proxy) = &config.proxy {
+        client = client.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    client = match config.http_version {
+        HttpVersion::Auto => client,
+        HttpVersion::Http1 => client.http1_only(),
+        HttpVersion::Http2 => client.http2_prior_knowledge(),
+    };
+
+    let credentials = &config.credentials;
+    let mut req = client
+        .build()?
+        .request(method, url)
+        .header("User-Agent", &config.user_agent);
+
+    if !credentials.cookies.is_empty() {
+        req = req.header("Cookie", credentials.cookies.join("; "));
+    }
+    if let Some(basic_auth) = &credentials.basic_auth {
+        let (user, password) = basic_auth.split_once(':').unwrap_or((basic_auth, ""));
+        req = req.basic_auth(user, Some(password));
+    }
+
+    Ok(req)
+}
+
+fn fetch_last_modified(base_url: &str, config: &FetchConfig) -> Result<Option<String>> {
+    let url = list_url(&parse_base_url(base_url)?, LIMIT, OFFSET);
+    let res = request(Method::HEAD, url.as_str(), config)?.send()?;
+
+    Ok(res
+        .headers()
+        .get(LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string))
+}
+
+fn fetch_page_once(url: &str, config: &FetchConfig) -> Result<String> {
+    let res = request(Method::GET, url, config)?.send()?;
+    let status = res.status();
+    if !status.is_success() {
+        // Error pages often say why, so keep the start of the body around.
+        let body = res.text().unwrap_or_default();
+        return Err(Error::Status {
+            url: url.to_string(),
+            status: status.as_u16(),
+            snippet: truncate_graphemes(body.trim(), STATUS_SNIPPET_LEN, ""),
+        });
+    }
+
+    let encoding = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(charset)
+        .unwrap_or(UTF_8);
+    let bytes = res.bytes()?;
+    let (text, encoding, had_errors) = encoding.decode(&bytes);
+    if had_errors {
+        warn!("invalid {} in {}; decoding lossily", encoding.name(), url);
+    }
+    Ok(text.into_owned())
+}
+
+/// The encoding named by the charset parameter of a Content-Type value.
+fn charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("charset") {
+            Encoding::for_label(value.trim().trim_matches('"').as_bytes())
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_body(html: &str) -> Option<String> {
+    let fragment = Html::parse_document(html);
+    let body_selector = Selector::parse("div.b-content-box").unwrap();
+
+    fragment
+        .select(&body_selector)
+        .next()
+        .map(|body| body.inner_html().trim().to_string())
+}
+
+fn sanitize_body(html: &str) -> String {
+    ammonia::Builder::empty()
+        .add_tags(&["p", "a", "ul", "li", "br", "strong", "em"])
+        .add_tag_attributes("a", &["href"])
+        .clean(html)
+        .to_string()
+}
+
+fn truncate_description(html: &str, link: &str, max_len: usize, read_more: &str) -> String {
+    // Cutting the markup itself could leave tags unclosed, so long bodies are
+    // flattened to text before being cut.
+    let text = decode(&ammonia::Builder::empty().clean(html).to_string());
+    if text.graphemes(true).count() <= max_len {
+        return html.to_string();
+    }
+
+    let truncated = truncate_graphemes(&text, max_len, "");
+    format!(
+        "{}… <a href=\"{}\">{}</a>",
+        encode_minimal(truncated.trim_end()),
+        encode_minimal(link),
+        read_more
+    )
+}
+
+fn preview_body(html: &str, max_len: usize) -> String {
+    let fragment = Html::parse_fragment(html);
+    let text = fragment
+        .root_element()
+        .descendants()
+        .filter(|node| {
+            let parent = node.parent().and_then(|parent| parent.value().as_element());
+            !parent.is_some_and(|parent| matches!(parent.name(), "script" | "style"))
+        })
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    encode_minimal(&truncate_graphemes(&text, max_len, "…"))
+}
+
+fn fetch_bodies(
+    notices: &mut [Notice],
+    base_url: &str,
+    max_in_flight: usize,
+    config: &FetchConfig,
+) {
+    // Notices that already have a body came from the cache. External links
+    // are left alone: the board's credentials must not go to another host.
+    let pending = notices
+        .iter()
+        .enumerate()
+        .filter(|(_, notice)| notice.body.is_none())
+        .filter(|(_, notice)| {
+            let external = !same_host(base_url, &notice.view_url(base_url));
+            if external {
+                debug!("not fetching the body of external link {}", notice.link);
+            }
+            !external
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+    let bodies = Mutex::new(vec![None; pending.len()]);
+    let links = pending
+        .iter()
+        .map(|&i| notices[i].view_url(base_url))
+        .collect::<Vec<_>>();
+
+    // Each worker claims the next unfetched notice, so at most max_in_flight
+    // requests are open at once and results keep the notices' order.
+    thread::scope(|scope| {
+        for _ in 0..max_in_flight.clamp(1, links.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= links.len() {
+                    break;
+                }
+
+                let body = match fetch_page(&links[i], config) {
+                    Ok(html) => parse_body(&html),
+                    Err(err) => {
+                        warn!("failed to fetch {}: {}", links[i], err);
+                        None
+                    }
+                };
+                bodies.lock().unwrap()[i] = body;
+            });
+        }
+    });
+
+    for (i, body) in pending.into_iter().zip(bodies.into_inner().unwrap()) {
+        notices[i].body = body;
+    }
+}
+
+fn parse_text(row: &ElementRef, selector: &Selector) -> String {
+    row.select(selector)
+        .flat_map(|datum| datum.text().collect::<Vec<_>>())
+        .map(|datum| datum.trim().replace(['\n', '\t'], ""))
+        .filter(|datum| !datum.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_attr(row: &ElementRef, selector: &Selector) -> String {
+    row.select(selector)
+        .flat_map(|datum| datum.value().attr("href"))
+        .collect::<Vec<_>>()
+        .first()
+        .unwrap_or(&"")
+        .to_string()
+}
+
+// Hrefs are relative to the board, except for the occasional post that links
+// straight to an external announcement.
+fn resolve_link(base_url: &str, href: &str) -> String {
+    let scheme = href.split_once("://").map(|(scheme, _)| scheme);
+    if scheme.is_some_and(|scheme| {
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    }) {
+        return href.to_string();
+    }
+    // Joined the way a browser would, so a rooted href or a base URL with a
+    // query string of its own still lands on the right page.
+    match Url::parse(base_url).and_then(|base| base.join(href)) {
+        Ok(url) => url.to_string(),
+        Err(_) => format!("{}{}", base_url, href),
+    }
+}
+
+fn related_links(row: &ElementRef, primary: &str, base_url: &str) -> Vec<String> {
+    let anchor_selector = Selector::parse("a[href]").unwrap();
+    let mut links = Vec::new();
+    for href in row
+        .select(&anchor_selector)
+        .flat_map(|anchor| anchor.value().attr("href"))
+    {
+        let href = href.trim();
+        if href.is_empty()
+            || href == primary
+            || href.starts_with('#')
+            || href.starts_with("javascript:")
+        {
+            continue;
+        }
+        let link = resolve_link(base_url, href);
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+fn parse_fallback(
+    row: &ElementRef,
+    selectors: &[&str],
+    parse: fn(&ElementRef, &Selector) -> String,
+) -> String {
+    selectors
+        .iter()
+        .find_map(|selector| {
+            let value = parse(row, &Selector::parse(selector).unwrap());
+            if value.is_empty() {
+                return None;
+            }
+            debug!("matched selector '{}'", selector);
+            Some(value)
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `link`, relative or absolute, points at the same host and port
+/// as the board.
+fn same_host(base_url: &str, link: &str) -> bool {
+    let host = |url: &str| {
+        Url::parse(url).ok().map(|url| {
+            (
+                url.host_str().map(str::to_string),
+                url.port_or_known_default(),
+            )
+        })
+    };
+    host(base_url).is_some_and(|board| host(&resolve_link(base_url, link)) == Some(board))
+}
+
+/// The article id of a link back to the board. An external post can carry
+/// an articleNo of its own that means nothing here.
+fn board_article_no(base_url: &str, href: &str) -> Option<String> {
+    if same_host(base_url, href) {
+        extract_article_no(href)
+    } else {
+        None
+    }
+}
+
+fn extract_article_no(link: &str) -> Option<String> {
+    let (_, query) = link.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "articleNo")
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_index(text: &str) -> Option<i32> {
+    // Some skins put an icon or a wrapper span next to the number, so take
+    // the first run of digits rather than the whole cell.
+    let digits = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    digits.parse::<i32>().ok()
+}
+
+fn has_badge(row: &ElementRef, selector: &Selector) -> bool {
+    row.select(selector).next().is_some()
+}
+
+fn parse_row(
+    row: &ElementRef,
+    selectors: &RowSelectors,
+    base_url: &str,
+    untitled: &str,
+    issues: &mut Vec<MissingField>,
+) -> std::result::Result<Notice, MissingField> {
+    let index_selector = Selector::parse(selectors.index).unwrap();
+    let category_selector = Selector::parse(selectors.category).unwrap();
+    let author_selector = Selector::parse(selectors.author).unwrap();
+    let expired_at_selector = Selector::parse(selectors.expired_at).unwrap();
+    let new_selector = Selector::parse(".b-new, img[alt~=\"new\"], img[src*=\"new\"]").unwrap();
+    let important_selector =
+        Selector::parse(".b-notice, .b-important, img[alt*=\"중요\"]").unwrap();
+    let attachment_selector =
+        Selector::parse(".b-file, img[alt*=\"첨부\"], img[src*=\"file\"]").unwrap();
+
+    let href = parse_fallback(row, selectors.titles, parse_attr);
+    if href.is_empty() {
+        return Err(MissingField::Link);
+    }
+
+    let mut title = parse_fallback(row, selectors.titles, parse_text);
+    if title.is_empty() {
+        warn!("notice {} has no title", href);
+        issues.push(MissingField::Title);
+        title = untitled.to_string();
+    }
+
+    let pinned = row.value().classes().any(|class| class == "b-top-box");
+    let index = parse_index(&parse_text(row, &index_selector));
+    if index.is_none() && !pinned {
+        issues.push(MissingField::Index);
+    }
+
+    let mut notice = Notice {
+        index: index.unwrap_or(-1),
+        category: parse_text(row, &category_selector),
+        title,
+        author: parse_text(row, &author_selector),
+        link: resolve_link(base_url, &href),
+        expired_at: parse_text(row, &expired_at_selector),
+        is_new: has_badge(row, &new_selector),
+        is_important: pinned || has_badge(row, &important_selector),
+        has_attachment: has_badge(row, &attachment_selector),
+        body: None,
+        article_no: board_article_no(base_url, &href),
+        updated: false,
+        first_seen: None,
+        related_links: related_links(row, &href, base_url),
+        raw_html: Some(row.html()),
+        priority: None,
+        source_offset: None,
+        page: None,
+        scraped_hash: None,
+    };
+    notice.scraped_hash = Some(fields_hash(&notice));
+    Ok(notice)
+}
+
+/// Parses the board rows, returning the notices along with a line for every
+/// row that was skipped or had to fall back to a default.
+fn parse_page(html: &str, base_url: &str, config: &ParseConfig) -> (Vec<Notice>, Vec<String>) {
+    match config.layout {
+        Layout::Table => parse_html(html, base_url, config.untitled),
+        Layout::Cards => parse_html_cards(html, base_url, config.untitled),
+        Layout::Auto => match parse_html(html, base_url, config.untitled) {
+            (notices, _) if notices.is_empty() => parse_html_cards(html, base_url, config.untitled),
+            parsed => parsed,
+        },
+    }
+}
+
+pub fn parse_html(html: &str, base_url: &str, untitled: &str) -> (Vec<Notice>, Vec<String>) {
+    let fragment = Html::parse_document(html);
+    let row_selector = Selector::parse(&format!("{} tr", TABLE_SELECTOR)).unwrap();
+    let header_selector = Selector::parse("th").unwrap();
+
+    // The raw HTML may or may not have an explicit <tbody>, so match any row
+    // and drop the header ones instead.
+    let rows = fragment.select(&row_selector).filter(|row| {
+        let in_thead = row
+            .parent()
+            .and_then(|parent| parent.value().as_element())
+            .is_some_and(|parent| parent.name() == "thead");
+        !in_thead && row.select(&header_selector).next().is_none()
+    });
+
+    parse_rows(rows, &TABLE_ROW, base_url, untitled)
+}
+
+fn parse_html_cards(html: &str, base_url: &str, untitled: &str) -> (Vec<Notice>, Vec<String>) {
+    let fragment = Html::parse_document(html);
+    let card_selector = Selector::parse(CARD_SELECTOR).unwrap();
+
+    parse_rows(
+        fragment.select(&card_selector),
+        &CARD_ROW,
+        base_url,
+        untitled,
+    )
+}
+
+fn parse_rows<'a>(
+    rows: impl Iterator<Item = ElementRef<'a>>,
+    selectors: &RowSelectors,
+    base_url: &str,
+    untitled: &str,
+) -> (Vec<Notice>, Vec<String>) {
+    let mut notices = Vec::new();
+    let mut row_errors = Vec::new();
+    let mut pinned = 0;
+
+    for (i, row) in rows.enumerate() {
+        let mut issues = Vec::new();
+        match parse_row(&row, selectors, base_url, untitled, &mut issues) {
+            Ok(mut notice) => {
+                // Pinned rows lose their number, so keep the board's order.
+                if notice.index == -1 {
+                    pinned += 1;
+                    notice.priority = Some(pinned);
+                }
+                notices.push(notice)
+            }
+            Err(err) => {
+                warn!("skipping row {}: {}", i + 1, err);
+                issues.push(err);
+            }
+        }
+        row_errors.extend(issues.iter().map(|err| format!("row {}: {}", i + 1, err)));
+    }
+
+    (notices, row_errors)
+}
+
+fn is_board_page(html: &str, layout: Layout) -> bool {
+    let fragment = Html::parse_document(html);
+    let board_selector = match layout {
+        Layout::Table => TABLE_SELECTOR.to_string(),
+        Layout::Cards => CARD_SELECTOR.to_string(),
+        Layout::Auto => format!("{}, {}", TABLE_SELECTOR, CARD_SELECTOR),
+    };
+    let board_selector = Selector::parse(&board_selector).unwrap();
+
+    fragment.select(&board_selector).next().is_some()
+}
+
+/// Whether a page without the board says it is down for maintenance. Only
+/// used to word the error, since notice titles can mention maintenance too.
+fn is_maintenance_page(html: &str) -> bool {
+    const MAINTENANCE_MARKERS: [&str; 3] = ["시스템 점검", "서비스 점검", "maintenance"];
+
+    let text = Html::parse_document(html)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .to_lowercase();
+    MAINTENANCE_MARKERS
+        .iter()
+        .any(|marker| text.contains(marker))
+}
+
+fn parse_total_count(html: &str) -> Option<u32> {
+    let fragment = Html::parse_document(html);
+    let text = fragment.root_element().text().collect::<String>();
+
+    text.split('총').skip(1).find_map(|rest| {
+        let rest = rest.trim_start();
+        let digits = rest
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',')
+            .collect::<String>();
+
+        if rest[digits.len()..].trim_start().starts_with('건') {
+            digits.replace(',', "").parse::<u32>().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[derive(Default)]
+struct Fetched {
+    notices: Vec<Notice>,
+    failed_offsets: Vec<u32>,
+    row_errors: Vec<String>,
+    fingerprint: Option<String>,
+}
+
+fn fetch_all(
+    base_url: &str,
+    limit: u8,
+    offset: u32,
+    pages: u32,
+    config: &FetchConfig,
+    parse_config: &ParseConfig,
+) -> Result<Fetched> {
+    let mut list_base_url = parse_base_url(base_url)?;
+    // Links point into the board itself, not into the search that listed them.
+    let link_base_url =
+        without_search(&list_base_url).map_or_else(|| base_url.to_string(), |url| url.to_string());
+    let mut fetched = Fetched::default();
+    let mut total_count = None;
+
+    'pages: for page in 0..pages {
+        let page_offset = offset + page * limit as u32;
+        if total_count.is_some_and(|total_count| page_offset >= total_count) {
+            break;
+        }
+
+        // The board briefly serves an empty table while it regenerates, so an
+        // empty page is worth another try before it is taken at face value.
+        let mut attempt = 0;
+        let (html, notices, row_errors) = loop {
+            let html = match fetch_html_with(config, &list_base_url, limit, page_offset) {
+                Ok(html) => html,
+                Err(err) => {
+                    warn!("failed to fetch offset {}: {}", page_offset, err);
+                    fetched.failed_offsets.push(page_offset);
+                    continue 'pages;
+                }
+            };
+            if !is_board_page(&html, parse_config.layout) {
+                return Err(Error::Parse(if is_maintenance_page(&html) {
+                    format!("offset {} returned a maintenance page", page_offset)
+                } else {
+                    format!(
+                        "offset {} did not return the notice board (error page?)",
+                        page_offset
+                    )
+                }));
+            }
+
+            let (notices, row_errors) = parse_page(&html, &link_base_url, parse_config);
+            if notices.is_empty() && attempt < config.empty_retries {
+                let delay = backoff_delay(config.backoff_base, attempt);
+                warn!(
+                    "offset {} had no rows, retrying in {:?}",
+                    page_offset, delay
+                );
+                thread::sleep(delay);
+                attempt += 1;
+                continue;
+            }
+            if notices.is_empty() && page == 0 && config.search_fallback {
+                if let Some(url) = without_search(&list_base_url) {
+                    warn!("search returned no rows, falling back to {}", url);
+                    list_base_url = url;
+                    attempt = 0;
+                    continue;
+                }
+            }
+            break (html, notices, row_errors);
+        };
+        if page == 0 && notices.len() < parse_config.min_rows {
+            return Err(Error::Parse(format!(
+                "offset {} has {} rows, fewer than --min-rows {}",
+                page_offset,
+                notices.len(),
+                parse_config.min_rows
+            )));
+        }
+        total_count = total_count.or_else(|| parse_total_count(&html));
+        if page == 0 {
+            fetched.fingerprint = layout_fingerprint(&html);
+        }
+
+        merge_page(&mut fetched.notices, notices, page, page_offset);
+        fetched.row_errors.extend(
+            row_errors
+                .into_iter()
+                .map(|err| format!("offset {}, {}", page_offset, err)),
+        );
+    }
+
+    Ok(fetched)
+}
+
+/// Appends a fetched page to the notices so far, tagging each notice with
+/// where it came from.
+pub fn merge_page(notices: &mut Vec<Notice>, page_notices: Vec<Notice>, page: u32, offset: u32) {
+    // Pinned notices are repeated at the top of every page.
+    notices.extend(
+        page_notices
+            .into_iter()
+            .filter(|notice| page == 0 || notice.index != -1)
+            .map(|notice| Notice {
+                source_offset: Some(offset),
+                page: Some(page),
+                ..notice
+            }),
+    );
+}
+
+/// The distinct classes on the cells of the first numbered row, which is
+/// what the row selectors lean on.
+fn layout_fingerprint(html: &str) -> Option<String> {
+    let fragment = Html::parse_document(html);
+    let row_selector = Selector::parse(&format!(
+        "{} tr:not(.b-top-box), {}:not(.b-top-box)",
+        TABLE_SELECTOR, CARD_SELECTOR
+    ))
+    .unwrap();
+    let header_selector = Selector::parse("th").unwrap();
+
+    let row = fragment
+        .select(&row_selector)
+        .find(|row| row.select(&header_selector).next().is_none())?;
+    let classes = row
+        .children()
+        .filter_map(ElementRef::wrap)
+        .flat_map(|cell| {
+            let name = cell.value().name();
+            cell.value()
+                .classes()
+                .map(move |class| format!("{}.{}", name, class))
+        })
+        .collect::<BTreeSet<_>>();
+    Some(classes.into_iter().collect::<Vec<_>>().join(" "))
+}
+
+fn check_layout(
+    board_state: &mut BoardState,
+    fingerprint: Option<String>,
+    abort: bool,
+) -> Result<()> {
+    let Some(fingerprint) = fingerprint else {
+        return Ok(());
+    };
+    if let Some(previous) = board_state
+        .layout_fingerprint
+        .as_ref()
+        .filter(|previous| **previous != fingerprint)
+    {
+        if abort {
+            return Err(Error::LayoutChanged {
+                previous: previous.clone(),
+                current: fingerprint,
+            });
+        }
+        warn!(
+            "board layout changed from '{}' to '{}'",
+            previous, fingerprint
+        );
+    }
+    board_state.layout_fingerprint = Some(fingerprint);
+    Ok(())
+}
+
+fn strict_error(row_errors: &[String]) -> Error {
+    Error::Parse(format!(
+        "{} malformed rows in strict mode:\n{}",
+        row_errors.len(),
+        row_errors.join("\n")
+    ))
+}
+
+fn percent_decode_title(title: &str) -> String {
+    // Titles that don't decode to valid UTF-8 are left as they are, so a
+    // literal '%' in ordinary text survives.
+    match percent_decode_str(title).decode_utf8() {
+        Ok(decoded) => decoded.into_owned(),
+        Err(_) => title.to_string(),
+    }
+}
+
+fn normalize_text(text: &str) -> String {
+    text.nfc()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{FF01}'..='\u{FF5E}' => {
+                let ascii = char::from_u32(c as u32 - 0xFEE0).unwrap_or(c);
+                if ascii.is_ascii_punctuation() {
+                    ascii
+                } else {
+                    c
+                }
+            }
+            _ => c,
+        })
+        .collect()
+}
+
+fn normalize_notices(notices: &mut [Notice]) {
+    for notice in notices.iter_mut() {
+        notice.title = normalize_text(&notice.title);
+        notice.author = normalize_text(&notice.author);
+        notice.category = normalize_text(&notice.category);
+        notice.expired_at = normalize_text(&notice.expired_at);
+    }
+}
+
+fn mark_first_seen(notices: &mut [Notice], board_state: &mut BoardState) {
+    let now = format_date(Utc::now(), DateFormat::Iso);
+    for notice in notices.iter_mut() {
+        notice.first_seen = Some(board_state.first_seen(notice_id(notice), &now));
+    }
+}
+
+fn rewrite_links(notices: &mut [Notice], options: &Options) {
+    if options.link_prefix.is_none() && options.link_replace.is_none() {
+        return;
+    }
+
+    for notice in notices.iter_mut() {
+        if let Some((from, to)) = &options.link_replace {
+            notice.link = notice.link.replace(from.as_str(), to);
+        }
+        if let Some(prefix) = &options.link_prefix {
+            notice.link = format!("{}{}", prefix, notice.link);
+        }
+    }
+}
+
+pub fn dedup_notices(notices: &mut Vec<Notice>, keep_pinned_duplicates: bool) {
+    // A pinned notice usually also shows up at its numbered position; keep
+    // the numbered one so the item has a real index.
+    let numbered = notices
+        .iter()
+        .filter(|notice| notice.index != -1)
+        .map(notice_id)
+        .collect::<HashSet<_>>();
+
+    let mut seen = HashSet::new();
+    notices.retain(|notice| {
+        let id = notice_id(notice);
+        if notice.index == -1 && !keep_pinned_duplicates && numbered.contains(&id) {
+            return false;
+        }
+        seen.insert((id, notice.index == -1))
+    });
+}
+
+fn new_notices(notices: &mut Vec<Notice>, last_index: i32) {
+    notices.retain(|notice| notice.index > last_index);
+}
+
+pub fn sort_notices(notices: &mut [Notice]) {
+    // Pinned notices stay on top in the board's order; ties fall back to the
+    // article id and the link so the order is total and stable.
+    notices.sort_by(|a, b| {
+        (b.index == -1)
+            .cmp(&(a.index == -1))
+            .then_with(|| b.index.cmp(&a.index))
+            .then_with(|| a.priority.cmp(&b.priority))
+            .then_with(|| a.article_no.cmp(&b.article_no))
+            .then_with(|| a.link.cmp(&b.link))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+}
+
+fn keep_top(notices: &mut Vec<Notice>, top: usize) {
+    // Expects sorted notices; pinned notices are kept and not counted.
+    let mut numbered = 0;
+    notices.retain(|notice| {
+        if notice.index == -1 {
+            return true;
+        }
+
+        numbered += 1;
+        numbered <= top
+    });
+}
+
+/// The cap on a feed's items, pinned ones included. The flag always wins
+/// over a board's config value; --top has already narrowed the numbered
+/// notices by then, so the two combine rather than compete.
+fn max_items(options: &Options, board: Option<&Board>) -> Option<usize> {
+    options
+        .max_items
+        .or_else(|| board.and_then(|board| board.max_items))
+}
+
+fn notice_id(notice: &Notice) -> String {
+    notice
+        .article_no
+        .clone()
+        .unwrap_or_else(|| notice.link.clone())
+}
+
+fn fnv1a(text: &str) -> String {
+    // FNV-1a keeps the hash stable across builds, unlike DefaultHasher.
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn fields_hash(notice: &Notice) -> String {
+    fnv1a(&format!(
+        "{}|{}|{}|{}",
+        notice.title, notice.category, notice.author, notice.expired_at
+    ))
+}
+
+fn content_hash(notice: &Notice) -> String {
+    notice
+        .scraped_hash
+        .clone()
+        .unwrap_or_else(|| fields_hash(notice))
+}
+
+fn items_hash(notices: &[Notice]) -> String {
+    fnv1a(
+        &notices
+            .iter()
+            .map(|notice| format!("{}={}", notice_id(notice), content_hash(notice)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn state_entry(notice: &Notice) -> (String, String) {
+    (notice_id(notice), content_hash(notice))
+}
+
+fn matches_regex(notice: &Notice, regex: &Regex) -> bool {
+    regex.is_match(&notice.title)
+        || notice
+            .body
+            .as_ref()
+            .is_some_and(|body| regex.is_match(body))
+}
+
+fn decode(text: &str) -> String {
+    decode_html(text).unwrap_or_else(|_| text.to_string())
+}
+
+fn display_title(notice: &Notice, max_len: Option<usize>, messages: &Messages) -> String {
+    let title = truncate_title(&notice.title, max_len);
+    if notice.updated {
+        format!("[{}] {}", messages.updated, title)
+    } else {
+        title
+    }
+}
+
+fn index_title(notice: &Notice, title: String, messages: &Messages) -> String {
+    match notice.index {
+        -1 => format!("[{}] {}", messages.pinned, title),
+        index => format!("[#{}] {}", index, title),
+    }
+}
+
+fn truncate_title(title: &str, max_len: Option<usize>) -> String {
+    match max_len {
+        Some(max_len) => truncate_graphemes(title, max_len, "…"),
+        None => title.to_string(),
+    }
+}
+
+/// Cuts `text` to at most `max` grapheme clusters, so Hangul syllables and
+/// emoji are never split, appending `ellipsis` only when something was cut.
+fn truncate_graphemes(text: &str, max: usize, ellipsis: &str) -> String {
+    let mut graphemes = text.graphemes(true);
+    let truncated = graphemes.by_ref().take(max).collect::<String>();
+    if graphemes.next().is_none() {
+        text.to_string()
+    } else {
+        format!("{}{}", truncated, ellipsis)
+    }
+}
+
+fn kst() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).unwrap()
+}
+
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    const FORMATS: [&str; 4] = ["%Y-%m-%d", "%Y.%m.%d", "%Y/%m/%d", "%Y년 %m월 %d일"];
+
+    // The board lists dates without a time, in Korea Standard Time.
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text.trim(), format).ok())
+        .and_then(|date| {
+            kst()
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .single()
+        })
+        .map(|date| date.with_timezone(&Utc))
+}
+
+fn format_deadline(expired_at: &str, relative: bool, messages: &Messages) -> String {
+    let deadline = match parse_date(expired_at) {
+        Some(deadline) if relative => deadline.with_timezone(&kst()).date_naive(),
+        _ => return format!("~{}", expired_at),
+    };
+
+    let today = Utc::now().with_timezone(&kst()).date_naive();
+    match (deadline - today).num_days() {
+        0 => "~D-DAY".to_string(),
+        days if days > 0 => format!("~D-{}", days),
+        _ => messages.closed.to_string(),
+    }
+}
+
+fn format_date(date: DateTime<Utc>, format: DateFormat) -> String {
+    match format {
+        DateFormat::Rss => date.to_rfc2822(),
+        DateFormat::Iso => date.to_rfc3339_opts(SecondsFormat::Secs, true),
+    }
+}
+
+fn iso_date(text: &str) -> Option<String> {
+    parse_date(text).map(|date| format_date(date, DateFormat::Iso))
+}
+
+pub fn compose_xml(
+    notices: &[Notice],
+    meta: &Meta,
+    self_url: Option<&str>,
+    author_emails: &HashMap<String, String>,
+    build_date: Option<&str>,
+    item_options: &RssItemOptions,
+) -> String {
+    let header = format!(
+        "<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+                  xmlns:atom=\"http://www.w3.org/2005/Atom\">\n \
+                  <channel>\n \
+                  <title>{}</title>\n \
+                  <link>{}</link>\n \
+                  <description>Recently published notices</description>\n \
+                  <language>ko-kr</language>\n \
+                  <generator>{} {}</generator>",
+        encode_minimal(meta.title),
+        encode_minimal(meta.home_page_url),
+        env!("CARGO_PKG_NAME"),
+        version(),
+    );
+    let header = match build_date {
+        Some(build_date) => format!("{}\n <lastBuildDate>{}</lastBuildDate>", header, build_date),
+        None => header,
+    };
+    let header = match self_url {
+        Some(self_url) => format!(
+            "{}\n <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+            header,
+            encode_minimal(self_url)
+        ),
+        None => header,
+    };
+
+    let footer = "</channel>\n \
+                  </rss>";
+
+    let items = notices
+        .iter()
+        .map(|notice| -> String {
+            let summary = encode_minimal(&format!(
+                "[{}] - {} (~{})",
+                notice.category, notice.author, notice.expired_at
+            ));
+            // The sanitized body goes into the description verbatim; the terse
+            // summary moves to dc:description so it isn't lost.
+            let description = match (&notice.body, item_options.full) {
+                (Some(body), true) => format!(
+                    "<description>{}</description>\n \
+                     <dc:description>{}</dc:description>",
+                    cdata(body),
+                    summary
+                ),
+                _ => format!("<description>{}</description>", summary),
+            };
+            let description = match item_options.comments_base_url {
+                Some(base_url) => format!(
+                    "{}\n <comments>{}</comments>",
+                    description,
+                    encode_minimal(&notice.view_url(base_url))
+                ),
+                None => description,
+            };
+            let description = match &notice.first_seen {
+                Some(first_seen) => format!("{}\n <dc:date>{}</dc:date>", description, first_seen),
+                None => description,
+            };
+            let author = match author_emails.get(&notice.author) {
+                Some(email) => format!(
+                    "<author>{} ({})</author>",
+                    encode_minimal(email),
+                    encode_minimal(&notice.author)
+                ),
+                None => format!(
+                    "<dc:creator>{}</dc:creator>",
+                    encode_minimal(&notice.author)
+                ),
+            };
+            let title = if item_options.index_in_title {
+                index_title(notice, notice.title.clone(), item_options.messages)
+            } else {
+                notice.title.clone()
+            };
+            let link = encode_minimal(&notice.link);
+            let guid = if item_options.guid_permalink {
+                format!("<guid isPermaLink=\"true\">{}</guid>", link)
+            } else {
+                format!(
+                    "<guid isPermaLink=\"false\">{}</guid>",
+                    encode_minimal(&notice_id(notice))
+                )
+            };
+            format!(
+                "<item>\n \
+                <title>{}</title>\n \
+                <link>{}</link>\n \
+                {}\n \
+                {}\n \
+                {}\n \
+                </item>",
+                encode_minimal(&title),
+                link,
+                guid,
+                description,
+                author
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("{}\n{}\n{}", header, items, footer)
+}
+
+fn to_json<T: Serialize>(value: &T, compact: bool) -> String {
+    if compact {
+        serde_json::to_string(value).unwrap()
+    } else {
+        serde_json::to_string_pretty(value).unwrap()
+    }
+}
+
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn compose_json_feed(notices: &[Notice], meta: &Meta, compact: bool) -> String {
+    let items = notices
+        .iter()
+        .map(|notice| JsonFeedItem {
+            id: notice_id(notice),
+            url: notice.link.clone(),
+            title: notice.title.clone(),
+            content_text: format!(
+                "[{}] - {} (~{})",
+                notice.category, notice.author, notice.expired_at
+            ),
+            content_html: notice.body.clone(),
+            expired_at: notice.expired_at.clone(),
+            iso_expired_at: iso_date(&notice.expired_at),
+        })
+        .collect::<Vec<_>>();
+
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: meta.title,
+        home_page_url: meta.home_page_url,
+        feed_url: meta.feed_url,
+        items,
+    };
+
+    to_json(&feed, compact)
+}
+
+fn split_author<'a>(author: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let (department, name) = author.trim().rsplit_once(delimiter)?;
+    let (department, name) = (department.trim(), name.trim());
+    if department.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some((department, name))
+}
+
+fn compose_json(
+    notices: &[Notice],
+    compact: bool,
+    author_delimiter: Option<&str>,
+    include_raw_html: bool,
+) -> String {
+    let notices = notices
+        .iter()
+        .map(|notice| {
+            let author = notice.author.clone();
+            let (department, author_name) = match author_delimiter
+                .and_then(|delimiter| split_author(&author, delimiter))
+            {
+                Some((department, name)) => (Some(department.to_string()), Some(name.to_string())),
+                None => (None, None),
+            };
+
+            JsonNotice {
+                index: notice.index,
+                article_no: notice.article_no.clone(),
+                title: notice.title.clone(),
+                author,
+                department,
+                author_name,
+                category: notice.category.clone(),
+                link: notice.link.clone(),
+                expired_at: notice.expired_at.clone(),
+                iso_expired_at: iso_date(&notice.expired_at),
+                is_new: notice.is_new,
+                is_important: notice.is_important,
+                has_attachment: notice.has_attachment,
+                body: notice.body.clone(),
+                updated: notice.updated,
+                related_links: notice.related_links.clone(),
+                raw_html: notice.raw_html.clone().filter(|_| include_raw_html),
+                priority: notice.priority,
+                source_offset: notice.source_offset,
+                page: notice.page,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    to_json(&notices, compact)
+}
+
+/// The JSON Schema of the json mode's output.
+fn json_schema() -> String {
+    let schema = schemars::schema_for!(Vec<JsonNotice>);
+    serde_json::to_string_pretty(&schema).unwrap()
+}
+
+/// The debug mode's output: each notice as parsed, one per line.
+fn debug_dump(notices: &[Notice]) -> String {
+    notices
+        .iter()
+        .map(|notice| format!("{:?}\n", notice))
+        .collect()
+}
+
+fn load_emoji_config(path: &str) -> Result<HashMap<String, String>> {
+    let config = fs::read_to_string(path)?;
+    let table = config
+        .parse::<toml::Table>()
+        .map_err(|err| Error::Config(format!("invalid emoji config '{}': {}", path, err)))?;
+
+    Ok(table
+        .into_iter()
+        .filter_map(|(category, emoji)| match emoji.as_str() {
+            Some(emoji) if emoji.graphemes(true).count() == 1 => {
+                Some((category, emoji.to_string()))
+            }
+            _ => {
+                warn!("ignoring emoji for '{}': {}", category, emoji);
+                None
+            }
+        })
+        .collect())
+}
+
+fn load_author_map(path: &str) -> Result<HashMap<String, String>> {
+    let config = fs::read_to_string(path)?;
+    let table = config
+        .parse::<toml::Table>()
+        .map_err(|err| Error::Config(format!("invalid author map '{}': {}", path, err)))?;
+
+    Ok(table
+        .into_iter()
+        .filter_map(|(author, name)| match name.as_str() {
+            Some(name) if !name.trim().is_empty() => Some((author, name.trim().to_string())),
+            _ => {
+                warn!("ignoring author mapping for '{}': {}", author, name);
+                None
+            }
+        })
+        .collect())
+}
+
+fn map_authors(notices: &mut [Notice], author_map: &HashMap<String, String>) {
+    for notice in notices.iter_mut() {
+        if let Some(name) = author_map.get(notice.author.trim()) {
+            notice.author = name.clone();
+        }
+    }
+}
+
+fn load_extra_notices(path: &str) -> Result<Vec<Notice>> {
+    let json = fs::read_to_string(path)?;
+    let extras = serde_json::from_str::<Vec<ExtraNotice>>(&json)
+        .map_err(|err| Error::Config(format!("invalid extra notices '{}': {}", path, err)))?;
+
+    extras
+        .into_iter()
+        .map(|extra| {
+            if extra.title.trim().is_empty() || extra.link.trim().is_empty() {
+                return Err(Error::Config(format!(
+                    "invalid extra notices '{}': title and link must not be empty",
+                    path
+                )));
+            }
+            Ok(Notice {
+                index: -1,
+                // Set against the board once it's known, see the merge in scrape.
+                article_no: None,
+                title: extra.title,
+                author: extra.author,
+                category: extra.category,
+                link: extra.link,
+                expired_at: extra.expired_at,
+                is_new: false,
+                is_important: true,
+                has_attachment: false,
+                body: None,
+                updated: false,
+                first_seen: None,
+                related_links: Vec::new(),
+                raw_html: None,
+                priority: None,
+                source_offset: None,
+                page: None,
+                scraped_hash: None,
+            })
+        })
+        .collect()
+}
+
+fn category_label(category: &str, emojis: &HashMap<String, String>) -> String {
+    match emojis.get(category) {
+        Some(emoji) => format!("{} {}", emoji, category),
+        None => category.to_string(),
+    }
+}
+
+fn sections<'a>(
+    notices: &'a [Notice],
+    separate_pinned: bool,
+    messages: &Messages,
+) -> Vec<(Option<&'static str>, Vec<&'a Notice>)> {
+    if !separate_pinned {
+        return vec![(None, notices.iter().collect())];
+    }
+
+    let (pinned, numbered) = notices
+        .iter()
+        .partition::<Vec<_>, _>(|notice| notice.index == -1);
+    vec![
+        (messages.pinned_section, pinned),
+        (messages.numbered_section, numbered),
+    ]
+    .into_iter()
+    .filter(|(_, notices)| !notices.is_empty())
+    .map(|(heading, notices)| (Some(heading), notices))
+    .collect()
+}
+
+fn compose_md(notices: &[Notice], md_options: &MdOptions) -> String {
+    let messages = md_options.messages;
+    let header = format!("# {}", messages.header);
+
+    let item = |notice: &Notice| -> String {
+        let title = display_title(notice, md_options.max_title_len, messages);
+        let title = if md_options.index_in_title {
+            index_title(notice, title, messages)
+        } else {
+            title
+        };
+        let title = if notice.index == -1 && md_options.pinned_marker {
+            format!("📌 {}", title)
+        } else {
+            title
+        };
+
+        let description = format!(
+            "[{}] - {} ({})",
+            category_label(&notice.category, md_options.emojis),
+            notice.author,
+            format_deadline(&notice.expired_at, md_options.relative_dates, messages)
+        );
+        format!(
+            r"* **[{}]({})**\n  {}",
+            encode_minimal(&title),
+            encode_minimal(&notice.link),
+            encode_minimal(&description)
+        )
+    };
+
+    let items = sections(notices, md_options.separate_pinned, messages)
+        .into_iter()
+        .map(|(heading, notices)| {
+            let items = notices
+                .into_iter()
+                .map(item)
+                .collect::<Vec<String>>()
+                .join(r"\n\n");
+            match heading {
+                Some(heading) => format!(r"## {}\n\n{}", heading, items),
+                None => items,
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(r"\n\n");
+
+    format!(r"{}\n\n{}", header, items)
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 48rem; margin: 2rem auto; \
+                          padding: 0 1rem; color: #222; }\n \
+                          ul { list-style: none; padding: 0; }\n \
+                          li { padding: 0.75rem 0; border-bottom: 1px solid #eee; }\n \
+                          li.pinned { background: #fffbe6; }\n \
+                          a { color: #0b57d0; text-decoration: none; }\n \
+                          .category { display: inline-block; padding: 0 0.4rem; margin-right: 0.4rem; \
+                          border-radius: 0.25rem; background: #e8eaed; font-size: 0.85em; }\n \
+                          .meta { color: #666; font-size: 0.9em; }";
+
+fn compose_html(notices: &[Notice], meta: &Meta) -> String {
+    let items = notices
+        .iter()
+        .map(|notice| {
+            let class = if notice.index == -1 {
+                " class=\"pinned\""
+            } else {
+                ""
+            };
+            let category = match notice.category.as_str() {
+                "" => String::new(),
+                category => format!(
+                    "<span class=\"category\">{}</span>",
+                    encode_minimal(category)
+                ),
+            };
+            format!(
+                "<li{}>{}<a href=\"{}\">{}</a>\n \
+                 <div class=\"meta\">{} · ~{}</div></li>",
+                class,
+                category,
+                encode_minimal(&notice.link),
+                encode_minimal(&notice.title),
+                encode_minimal(&notice.author),
+                encode_minimal(&notice.expired_at)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"ko\">\n\
+         <head>\n \
+         <meta charset=\"utf-8\">\n \
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n \
+         <title>{}</title>\n \
+         <style>{}</style>\n\
+         </head>\n\
+         <body>\n \
+         <h1><a href=\"{}\">{}</a></h1>\n \
+         <ul>\n{}\n</ul>\n\
+         </body>\n\
+         </html>",
+        encode_minimal(meta.title),
+        HTML_STYLE,
+        encode_minimal(meta.home_page_url),
+        encode_minimal(meta.title),
+        items
+    )
+}
+
+fn compose_text(
+    notices: &[Notice],
+    relative_dates: bool,
+    emojis: &HashMap<String, String>,
+    separate_pinned: bool,
+    messages: &Messages,
+) -> String {
+    let header = messages.header;
+
+    let item = |notice: &Notice| -> String {
+        format!(
+            "• {}\n  [{}] {} {}\n  {}",
+            display_title(notice, None, messages),
+            category_label(&notice.category, emojis),
+            notice.author,
+            format_deadline(&notice.expired_at, relative_dates, messages),
+            notice.link
+        )
+    };
+
+    let items = sections(notices, separate_pinned, messages)
+        .into_iter()
+        .map(|(heading, notices)| {
+            let items = notices
+                .into_iter()
+                .map(item)
+                .collect::<Vec<String>>()
+                .join("\n\n");
+            match heading {
+                Some(heading) => format!("{}\n\n{}", heading, items),
+                None => items,
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    format!("{}\n\n{}", header, items)
+}
+
+fn compose_commit_message(
+    notices: &[Notice],
+    new_count: i32,
+    max_title_len: Option<usize>,
+    wrap_width: usize,
+    messages: &Messages,
+) -> String {
+    let header = format!("dist: {}{}", new_count, messages.new_notices);
+
+    let items = notices
+        .iter()
+        .map(|notice| {
+            let options = textwrap::Options::new(wrap_width)
+                .initial_indent("* ")
+                .subsequent_indent("  ")
+                .word_separator(textwrap::WordSeparator::AsciiSpace);
+            textwrap::fill(&display_title(notice, max_title_len, messages), options)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("{}\n\n{}", header, items)
+}
+
+fn digest_notices<'a>(notices: &'a [Notice], options: &Options) -> Cow<'a, [Notice]> {
+    if !options.dedup_titles {
+        return Cow::Borrowed(notices);
+    }
+
+    // Re-posts show up as the same title twice in a row; feed readers
+    // dedupe on their own, so only the digests collapse them.
+    let mut notices = notices.to_vec();
+    notices.dedup_by(|a, b| a.title == b.title && a.link == b.link);
+    Cow::Owned(notices)
+}
+
+fn romanize_titles<'a>(notices: Cow<'a, [Notice]>, options: &Options) -> Cow<'a, [Notice]> {
+    if !options.romanize {
+        return notices;
+    }
+
+    let mut notices = notices.into_owned();
+    for notice in notices.iter_mut() {
+        if let Some(romanized) = romanize(&notice.title) {
+            notice.title = format!("{} ({})", notice.title, romanized);
+        }
+    }
+    Cow::Owned(notices)
+}
+
+trait Composer {
+    fn compose(&self, notices: &[Notice]) -> Result<String>;
+}
+
+struct RssComposer<'a> {
+    meta: Meta<'a>,
+    self_url: Option<&'a str>,
+    author_emails: &'a HashMap<String, String>,
+    build_date: Option<&'a str>,
+    item_options: RssItemOptions<'a>,
+}
+
+impl Composer for RssComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        Ok(compose_xml(
+            notices,
+            &self.meta,
+            self.self_url,
+            self.author_emails,
+            self.build_date,
+            &self.item_options,
+        ))
+    }
+}
+
+struct JsonComposer<'a> {
+    compact: bool,
+    author_delimiter: Option<&'a str>,
+    include_raw_html: bool,
+}
+
+impl Composer for JsonComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        Ok(compose_json(
+            notices,
+            self.compact,
+            self.author_delimiter,
+            self.include_raw_html,
+        ))
+    }
+}
+
+struct JsonFeedComposer<'a> {
+    meta: Meta<'a>,
+    compact: bool,
+}
+
+impl Composer for JsonFeedComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        Ok(compose_json_feed(notices, &self.meta, self.compact))
+    }
+}
+
+struct HtmlComposer<'a> {
+    meta: Meta<'a>,
+}
+
+impl Composer for HtmlComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        Ok(compose_html(notices, &self.meta))
+    }
+}
+
+struct MdComposer<'a> {
+    options: &'a Options,
+}
+
+impl Composer for MdComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        let notices = romanize_titles(digest_notices(notices, self.options), self.options);
+        Ok(compose_md(&notices, &self.options.md_options()))
+    }
+}
+
+struct TextComposer<'a> {
+    options: &'a Options,
+}
+
+impl Composer for TextComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        let notices = romanize_titles(digest_notices(notices, self.options), self.options);
+        Ok(compose_text(
+            &notices,
+            self.options.relative_dates,
+            &self.options.category_emojis,
+            self.options.separate_pinned,
+            self.options.locale.messages(),
+        ))
+    }
+}
+
+struct CommitMessageComposer<'a> {
+    options: &'a Options,
+    new_count: i32,
+}
+
+impl Composer for CommitMessageComposer<'_> {
+    fn compose(&self, notices: &[Notice]) -> Result<String> {
+        let notices = digest_notices(notices, self.options);
+        Ok(compose_commit_message(
+            &notices,
+            self.new_count,
+            self.options.max_title_len,
+            self.options.wrap_width,
+            self.options.locale.messages(),
+        ))
+    }
+}
+
+fn composer<'a>(
+    mode: &str,
+    options: &'a Options,
+    build_date: Option<&'a str>,
+    new_count: i32,
+) -> Option<Box<dyn Composer + 'a>> {
+    match mode {
+        "xml" => Some(Box::new(RssComposer {
+            meta: options.meta(),
+            self_url: options.feed_url.as_deref(),
+            author_emails: &options.author_emails,
+            build_date,
+            item_options: options
+                .rss_item_options(options.human_url.as_deref().unwrap_or(&options.base_url)),
+        })),
+        "json" => Some(Box::new(JsonComposer {
+            compact: options.compact_json,
+            author_delimiter: options.author_delimiter.as_deref(),
+            include_raw_html: options.include_raw_html,
+        })),
+        "jsonfeed" => Some(Box::new(JsonFeedComposer {
+            meta: options.meta(),
+            compact: options.compact_json,
+        })),
+        "md" => Some(Box::new(MdComposer { options })),
+        "html" => Some(Box::new(HtmlComposer {
+            meta: options.meta(),
+        })),
+        "text" => Some(Box::new(TextComposer { options })),
+        "cm" => Some(Box::new(CommitMessageComposer { options, new_count })),
+        _ => None,
+    }
+}
+
+fn compose_output(
+    composer: &dyn Composer,
+    notices: &[Notice],
+    options: &Options,
+) -> Result<String> {
+    let compose = |notices: &[Notice]| -> Result<String> {
+        let output = format!("{}\n", composer.compose(notices)?);
+        Ok(convert_line_endings(&output, options.line_ending))
+    };
+
+    let output = compose(notices)?;
+    let limit = match options.max_output_bytes {
+        Some(limit) if output.len() > limit => limit,
+        _ => return Ok(output),
+    };
+    if options.max_output_error {
+        return Err(Error::OutputTooLarge {
+            size: output.len(),
+            limit,
+        });
+    }
+
+    // Drop items from the end until the rest fits, leaving a marker item so
+    // readers know the feed was cut.
+    for kept in (0..notices.len()).rev() {
+        let mut truncated = notices[..kept].to_vec();
+        truncated.push(omitted_notice(notices.len() - kept, options));
+        let output = compose(&truncated)?;
+        if output.len() <= limit {
+            warn!("output truncated to {} of {} notices", kept, notices.len());
+            return Ok(output);
+        }
+    }
+    Err(Error::OutputTooLarge {
+        size: output.len(),
+        limit,
+    })
+}
+
+fn omitted_notice(count: usize, options: &Options) -> Notice {
+    Notice {
+        index: 0,
+        title: format!("{} ({})", options.locale.messages().omitted, count),
+        author: String::new(),
+        category: String::new(),
+        link: options.meta().home_page_url.to_string(),
+        expired_at: String::new(),
+        is_new: false,
+        is_important: false,
+        has_attachment: false,
+        body: None,
+        article_no: None,
+        updated: false,
+        first_seen: None,
+        related_links: Vec::new(),
+        raw_html: None,
+        priority: None,
+        source_offset: None,
+        page: None,
+        scraped_hash: None,
+    }
+}
+
+fn write_xlsx(
+    notices: &[Notice],
+    path: &str,
+    emit_checksum: bool,
+    messages: &Messages,
+) -> Result<()> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+
+    for (col, header) in messages.columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &bold)?;
+    }
+    for (i, notice) in notices.iter().enumerate() {
+        let row = i as u32 + 1;
+        let index = match notice.index {
+            -1 => messages.pinned.to_string(),
+            index => index.to_string(),
+        };
+
+        sheet.write_string(row, 0, index)?;
+        sheet.write_string(row, 1, &notice.category)?;
+        sheet.write_string(row, 2, &notice.title)?;
+        sheet.write_string(row, 3, &notice.author)?;
+        sheet.write_string(row, 4, &notice.expired_at)?;
+        sheet.write_url(row, 5, notice.link.as_str())?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.set_column_width(2, 60)?;
+
+    write_output(path, &workbook.save_to_buffer()?, emit_checksum)
+}
+
+fn write_output(path: &str, contents: &[u8], emit_checksum: bool) -> Result<()> {
+    fs::write(path, contents)?;
+    if emit_checksum {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let digest = Sha256::digest(contents)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        fs::write(
+            format!("{}.sha256", path),
+            format!("{}  {}\n", digest, name),
+        )?;
+    }
+    Ok(())
+}
+
+fn convert_line_endings(text: &str, line_ending: LineEnding) -> String {
+    let text = text.replace("\r\n", "\n");
+    match line_ending {
+        LineEnding::Lf => text,
+        LineEnding::Crlf => text.replace('\n', "\r\n"),
+    }
+}
+
+fn compose_opml(boards: &[Board]) -> String {
+    let outlines = boards
+        .iter()
+        .map(|board| {
+            format!(
+                "<outline type=\"rss\" text=\"{}\" xmlUrl=\"{}\" htmlUrl=\"{}\"/>",
+                encode_minimal(&board.title),
+                encode_minimal(&board.feed_url),
+                encode_minimal(&board.home_page_url)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "<opml version=\"2.0\">\n \
+         <head><title>Ajou University Notices</title></head>\n \
+         <body>\n{}\n</body>\n \
+         </opml>",
+        outlines
+    )
+}
+
+/// Flags that only apply to a single board's run and that multi mode
+/// would otherwise silently ignore.
+fn unsupported_in_multi(options: &Options) -> Vec<&'static str> {
+    [
+        ("a last index", options.last_index.is_some()),
+        ("--only-new", options.only_new),
+        ("--top", options.top.is_some()),
+        ("--filter-regex", options.filter_regex.is_some()),
+        ("--filter-expr", options.filter_expr.is_some()),
+        ("--with-body", options.with_body),
+        ("--preview-chars", options.preview_chars.is_some()),
+        ("--extra-notice-file", !options.extra_notices.is_empty()),
+        ("--new-by-badge", options.new_by_badge),
+        ("--recent-runs", options.recent_runs.is_some()),
+        ("--check-last-modified", options.check_last_modified),
+        ("--fail-on-empty", options.fail_on_empty),
+        ("--split-by-category", options.split_by_category),
+        ("--max-output-bytes", options.max_output_bytes.is_some()),
+        ("--output", options.output.is_some()),
+        ("--publish-gist", options.publish_gist),
+        ("--post-webhook", options.post_webhook.is_some()),
+    ]
+    .iter()
+    .filter(|(_, set)| *set)
+    .map(|(flag, _)| *flag)
+    .collect()
+}
+
+fn run_multi(options: &Options) -> Result<()> {
+    let unsupported = unsupported_in_multi(options);
+    if !unsupported.is_empty() {
+        return Err(Error::Config(format!(
+            "multi mode does not support {}",
+            unsupported.join(", ")
+        )));
+    }
+
+    let config = fs::read_to_string(&options.config)?;
+    let config = serde_json::from_str::<Config>(&config)
+        .map_err(|err| Error::Config(format!("invalid config '{}': {}", options.config, err)))?;
+    let state_path = options.state.as_deref().unwrap_or(DEFAULT_STATE_PATH);
+    let mut state = load_state(state_path)?;
+
+    // Every board is scraped before anything is written, so a failure on one
+    // board leaves all the feeds and the state as they were.
+    let mut outputs = Vec::new();
+    for board in &config.boards {
+        let Fetched {
+            mut notices,
+            failed_offsets,
+            row_errors,
+            fingerprint,
+        } = fetch_all(
+            &board.base_url,
+            LIMIT,
+            OFFSET,
+            options.pages,
+            &options.fetch,
+            &options.parse_config(),
+        )?;
+        if options.strict && !row_errors.is_empty() {
+            return Err(strict_error(&row_errors));
+        }
+        check_layout(
+            state.boards.entry(board.id.clone()).or_default(),
+            fingerprint,
+            options.abort_on_layout_change,
+        )?;
+        if !failed_offsets.is_empty() && !options.allow_partial {
+            warn!(
+                "skipping board '{}': failed offsets {:?}",
+                board.id, failed_offsets
+            );
+            continue;
+        }
+        if options.percent_decode {
+            for notice in notices.iter_mut() {
+                notice.title = percent_decode_title(&notice.title);
+            }
+        }
+        if options.normalize {
+            normalize_notices(&mut notices);
+        }
+        map_authors(&mut notices, &options.author_map);
+        dedup_notices(&mut notices, options.keep_pinned_duplicates);
+        sort_notices(&mut notices);
+        if let Some(max_items) = max_items(options, Some(board)) {
+            notices.truncate(max_items);
+        }
+
+        let latest_index = match notices.iter().find(|notice| notice.index != -1) {
+            Some(notice) => notice.index,
+            None => {
+                warn!("no numbered notices found for board '{}'", board.id);
+                continue;
+            }
+        };
+
+        let board_state = state.boards.entry(board.id.clone()).or_default();
+        if board_state.last_index == latest_index {
+            eprintln!("new notices not found for board '{}'", board.id);
+            continue;
+        }
+        if options.emit_first_seen {
+            mark_first_seen(&mut notices, board_state);
+        }
+        rewrite_links(&mut notices, options);
+
+        let meta = Meta {
+            title: &board.title,
+            home_page_url: &board.home_page_url,
+            feed_url: &board.feed_url,
+        };
+        let path = format!("{}/{}.xml", options.output_dir, board.id);
+        let build_date = format_date(Utc::now(), DateFormat::Rss);
+        let xml = compose_xml(
+            &notices,
+            &meta,
+            Some(&board.feed_url),
+            &options.author_emails,
+            (!options.deterministic).then_some(build_date.as_str()),
+            &options.rss_item_options(&board.base_url),
+        );
+        outputs.push((path, convert_line_endings(&xml, options.line_ending)));
+        board_state.record(
+            latest_index,
+            notices.iter().map(state_entry),
+            options.dedup_window,
+        );
+    }
+
+    for (path, xml) in &outputs {
+        write_output(path, xml.as_bytes(), options.emit_checksum)?;
+    }
+    let path = format!("{}/feeds.opml", options.output_dir);
+    let opml = compose_opml(&config.boards);
+    let opml = convert_line_endings(&opml, options.line_ending);
+    write_output(&path, opml.as_bytes(), options.emit_checksum)?;
+    save_state(state_path, &state)
+}
+
+fn feed_filename(name: &str, mode: &str) -> String {
+    let extension = match mode {
+        "xml" => "xml",
+        "json" | "jsonfeed" => "json",
+        "md" => "md",
+        "html" => "html",
+        _ => "txt",
+    };
+    format!("{}.{}", name, extension)
+}
+
+fn slugify(text: &str) -> String {
+    let slug = text
+        .trim()
+        .chars()
+        .map(|c| match c {
+            c if c.is_whitespace() => '-',
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect::<String>();
+    slug.trim_matches('-').to_string()
+}
+
+fn write_by_category(
+    notices: &[Notice],
+    composer: &dyn Composer,
+    mode: &str,
+    options: &Options,
+) -> Result<()> {
+    let mut groups = BTreeMap::<String, Vec<&Notice>>::new();
+    for notice in notices {
+        let slug = match slugify(&notice.category) {
+            slug if slug.is_empty() => "misc".to_string(),
+            slug => slug,
+        };
+        groups.entry(slug).or_default().push(notice);
+    }
+
+    for (slug, group) in groups {
+        let group = group.into_iter().cloned().collect::<Vec<_>>();
+        let output = compose_output(composer, &group, options)?;
+        let path = format!("{}/{}", options.output_dir, feed_filename(&slug, mode));
+        write_output(&path, output.as_bytes(), options.emit_checksum)?;
+    }
+
+    Ok(())
+}
+
+fn publish_gist(content: &str, filename: &str) -> Result<String> {
+    let token = env::var("GITHUB_TOKEN")
+        .map_err(|_| Error::Config("GITHUB_TOKEN is not set".to_string()))?;
+    let api_url =
+        env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".to_string());
+    post_gist(&api_url, &token, content, filename)
+}
+
+/// Creates a secret gist holding `content` and returns its raw URL.
+fn post_gist(api_url: &str, token: &str, content: &str, filename: &str) -> Result<String> {
+    let url = format!("{}/gists", api_url.trim_end_matches('/'));
+
+    let body = serde_json::json!({
+        "description": META.title,
+        "public": false,
+        "files": { filename: { "content": content } },
+    });
+    let res = reqwest::blocking::Client::new()
+        .post(&url)
+        .header("User-Agent", user_agent())
+        .header("Accept", "application/vnd.github+json")
+        .bearer_auth(token)
+        .body(body.to_string())
+        .send()?;
+
+    let status = res.status();
+    let text = res.text()?;
+    if !status.is_success() {
+        return Err(Error::Status {
+            url,
+            status: status.as_u16(),
+            snippet: truncate_graphemes(text.trim(), STATUS_SNIPPET_LEN, ""),
+        });
+    }
+
+    let gist = serde_json::from_str::<serde_json::Value>(&text)
+        .map_err(|err| Error::Parse(format!("invalid gist response: {}", err)))?;
+    gist["files"][filename]["raw_url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| Error::Parse("gist response has no raw_url".to_string()))
+}
+
+fn content_type(mode: &str) -> &'static str {
+    match mode {
+        "xml" => "application/rss+xml; charset=utf-8",
+        "json" => "application/json; charset=utf-8",
+        "jsonfeed" => "application/feed+json; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "html" => "text/html; charset=utf-8",
+        _ => "text/plain; charset=utf-8",
+    }
+}
+
+/// A POST to a third party. Unlike `request` it leaves out the board's
+/// credentials and proxy, and always verifies certificates: --insecure is
+/// there for the board's certificate, not for wherever the feed is sent.
+fn webhook_request(url: &str, config: &FetchConfig) -> Result<RequestBuilder> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(config.timeout)
+        .build()?;
+    Ok(client.post(url).header("User-Agent", &config.user_agent))
+}
+
+fn post_webhook(url: &str, content: &str, mode: &str, config: &FetchConfig) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        let res = webhook_request(url, config)?
+            .header("Content-Type", content_type(mode))
+            .body(content.to_string())
+            .send()
+            .map_err(Error::from)
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    return Ok(());
+                }
+                let body = res.text().unwrap_or_default();
+                Err(Error::Status {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                    snippet: truncate_graphemes(body.trim(), STATUS_SNIPPET_LEN, ""),
+                })
+            });
+        match res {
+            Err(err) if attempt < config.retries && is_retryable(&err) => {
+                let delay = backoff_delay(config.backoff_base, attempt);
+                warn!("retrying {} in {:?}: {}", url, delay, err);
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// `run`, posting any error to --error-webhook before handing it back.
+fn run_reporting_errors(options: &Options) -> Result<()> {
+    let result = run(options);
+    if let (Err(err), Some(url)) = (&result, &options.error_webhook) {
+        notify_error(url, err);
+    }
+    result
+}
+
+fn notify_error(url: &str, err: &Error) {
+    let payload = serde_json::json!({
+        "error": err.to_string(),
+        "exit_code": err.exit_code(),
+        "version": version(),
+    });
+    let res = reqwest::blocking::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(payload.to_string())
+        .send()
+        .and_then(|res| res.error_for_status());
+
+    // The original error is what gets reported; a failed notification only
+    // leaves a warning behind.
+    if let Err(notify_err) = res {
+        warn!("failed to notify {}: {}", url, notify_err);
+    }
+}
+
+fn write_last_index(last_index: i32) -> Result<()> {
+    let current_exe = env::current_exe()?;
+    let current_dir = current_exe.parent().unwrap_or(&current_exe);
+    let path = format!("{}/last_index", current_dir.display());
+    let mut file = File::create(path)?;
+    file.write_all(last_index.to_string().as_bytes())?;
+    Ok(())
+}
+
+fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// `version()` with the build SHA, for --version only: anywhere else it
+/// would change the feed or the User-Agent on every rebuild.
+fn long_version() -> String {
+    match option_env!("GIT_SHA") {
+        Some(sha) => format!("{}+{}", version(), sha),
+        None => version().to_string(),
+    }
+}
+
+fn user_agent() -> String {
+    format!(
+        "Mozilla/5.0 (compatible; {}/{})",
+        env!("CARGO_PKG_NAME"),
+        version()
+    )
+}
+
+fn next_arg(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| Error::Config(format!("missing value for {}", flag)))
+}
+
+fn next_parsed<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T>
+where
+    T::Err: fmt::Display,
+{
+    let value = next_arg(args, flag)?;
+    value
+        .parse::<T>()
+        .map_err(|err| Error::Config(format!("invalid {} '{}': {}", flag, value, err)))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    parse_args_with_stdin(args, &mut io::stdin().lock())
+}
+
+/// Like `parse_args`, reading a `-` last index from `stdin`.
+fn parse_args_with_stdin(
+    args: impl Iterator<Item = String>,
+    stdin: &mut impl io::BufRead,
+) -> Result<Options> {
+    let mut options = Options {
+        pages: 1,
+        max_in_flight: 4,
+        dedup_window: DEFAULT_DEDUP_WINDOW,
+        interval_secs: 300,
+        wrap_width: 72,
+        output_dir: ".".to_string(),
+        base_url: BASE_URL.to_string(),
+        ..Default::default()
+    };
+    let mut positional = Vec::new();
+
+    let mut args = args.skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--version" => {
+                println!("{} {}", env!("CARGO_PKG_NAME"), long_version());
+                process::exit(0);
+            }
+            "--no-pinned-marker" => options.no_pinned_marker = true,
+            "--fail-on-empty" => options.fail_on_empty = true,
+            "--strict" => options.strict = true,
+            "--check-last-modified" => options.check_last_modified = true,
+            "--emit-checksum" => options.emit_checksum = true,
+            "--locale" => options.locale = next_parsed(&mut args, &arg)?,
+            "--publish-gist" => options.publish_gist = true,
+            "--split-author" => {
+                options
+                    .author_delimiter
+                    .get_or_insert_with(|| " ".to_string());
+            }
+            "--author-delimiter" => options.author_delimiter = Some(next_arg(&mut args, &arg)?),
+            "--split-by-category" => options.split_by_category = true,
+            "--keep-pinned-duplicates" => options.keep_pinned_duplicates = true,
+            "--max-title-len" => options.max_title_len = Some(next_parsed(&mut args, &arg)?),
+            "--with-body" => options.with_body = true,
+            "--max-description-len" => {
+                options.max_description_len = Some(next_parsed(&mut args, &arg)?)
+            }
+            "--emit-first-seen" => options.emit_first_seen = true,
+            "--full" => {
+                options.full = true;
+                options.with_body = true;
+            }
+            "--raw-body" => options.raw_body = true,
+            "--preview-chars" => options.preview_chars = Some(next_parsed(&mut args, &arg)?),
+            "--max-in-flight" => options.max_in_flight = next_parsed(&mut args, &arg)?,
+            "--config" => options.config = next_arg(&mut args, &arg)?,
+            "--state" => options.state = Some(next_arg(&mut args, &arg)?),
+            "--error-webhook" => options.error_webhook = Some(next_arg(&mut args, &arg)?),
+            "--post-webhook" => options.post_webhook = Some(next_arg(&mut args, &arg)?),
+            "--output" => options.output = Some(next_arg(&mut args, &arg)?),
+            "--feed-url" => options.feed_url = Some(next_arg(&mut args, &arg)?),
+            "--human-url" => options.human_url = Some(next_arg(&mut args, &arg)?),
+            "--output-dir" => options.output_dir = next_arg(&mut args, &arg)?,
+            "--top" => options.top = Some(next_parsed(&mut args, &arg)?),
+            "--max-items" => options.max_items = Some(next_parsed(&mut args, &arg)?),
+            "--timeout-secs" => {
+                options.fetch.timeout = Some(Duration::from_secs(next_parsed(&mut args, &arg)?))
+            }
+            "--retries" => options.fetch.retries = next_parsed(&mut args, &arg)?,
+            "--empty-retries" => options.fetch.empty_retries = next_parsed(&mut args, &arg)?,
+            "--http-version" => options.fetch.http_version = next_parsed(&mut args, &arg)?,
+            "--search-fallback" => options.fetch.search_fallback = true,
+            "--abort-on-layout-change" => options.abort_on_layout_change = true,
+            "--base-url" => options.base_url = next_arg(&mut args, &arg)?,
+            "--backoff-ms" => {
+                options.fetch.backoff_base = Duration::from_millis(next_parsed(&mut args, &arg)?)
+            }
+            "--user-agent" => options.fetch.user_agent = next_arg(&mut args, &arg)?,
+            "--proxy" => options.fetch.proxy = Some(next_arg(&mut args, &arg)?),
+            "--verify-tls" => options.fetch.insecure = false,
+            "--cookie" => options
+                .fetch
+                .credentials
+                .cookies
+                .push(next_arg(&mut args, &arg)?),
+            "--basic-auth" => {
+                options.fetch.credentials.basic_auth = Some(next_arg(&mut args, &arg)?)
+            }
+            "--guid-permalink" => options.guid_permalink = next_parsed(&mut args, &arg)?,
+            "--wrap-width" => options.wrap_width = next_parsed(&mut args, &arg)?,
+            "--compact-json" => options.compact_json = true,
+            "--link-prefix" => options.link_prefix = Some(next_arg(&mut args, &arg)?),
+            "--link-replace" => {
+                options.link_replace =
+                    Some((next_arg(&mut args, &arg)?, next_arg(&mut args, &arg)?))
+            }
+            "--emit-comments" => options.emit_comments = true,
+            "--only-new" => options.only_new = true,
+            "--new-by-badge" => options.new_by_badge = true,
+            "--separate-pinned" => options.separate_pinned = true,
+            "--dedup-titles" => options.dedup_titles = true,
+            "--romanize" => options.romanize = true,
+            "--quiet" => options.quiet = true,
+            "--recent-runs" => options.recent_runs = Some(next_parsed(&mut args, &arg)?),
+            "--max-output-bytes" => options.max_output_bytes = Some(next_parsed(&mut args, &arg)?),
+            "--max-output-error" => options.max_output_error = true,
+            "--include-raw-html" => options.include_raw_html = true,
+            "--layout" => options.layout = next_parsed(&mut args, &arg)?,
+            "--normalize" => options.normalize = true,
+            "--percent-decode" => options.percent_decode = true,
+            "--index-in-title" => options.index_in_title = true,
+            "--relative-dates" => options.relative_dates = true,
+            "--deterministic" => options.deterministic = true,
+            "--stable-build-date" => options.stable_build_date = true,
+            "--allow-partial" => options.allow_partial = true,
+            "--min-rows" => options.min_rows = next_parsed(&mut args, &arg)?,
+            "--filter-regex" => options.filter_regex = Some(next_parsed(&mut args, &arg)?),
+            "--filter-expr" => options.filter_expr = Some(next_parsed(&mut args, &arg)?),
+            "--line-ending" => {
+                options.line_ending = match next_arg(&mut args, &arg)?.as_str() {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::Crlf,
+                    line_ending => {
+                        return Err(Error::Config(format!(
+                            "unknown line ending '{}'",
+                            line_ending
+                        )))
+                    }
+                }
+            }
+            "--emoji-config" => {
+                options.category_emojis = load_emoji_config(&next_arg(&mut args, &arg)?)?
+            }
+            "--dedup-window" => options.dedup_window = next_parsed(&mut args, &arg)?,
+            "--map-author-file" => {
+                options.author_map = load_author_map(&next_arg(&mut args, &arg)?)?
+            }
+            "--extra-notice-file" => {
+                options.extra_notices = load_extra_notices(&next_arg(&mut args, &arg)?)?
+            }
+            "--interval-secs" => options.interval_secs = next_parsed(&mut args, &arg)?,
+            "--pages" => options.pages = next_parsed(&mut args, &arg)?,
+            "--author-email" => {
+                let mapping = next_arg(&mut args, &arg)?;
+                let (author, email) = mapping.split_once('=').ok_or_else(|| {
+                    Error::Config(format!("invalid --author-email '{}'", mapping))
+                })?;
+                options
+                    .author_emails
+                    .insert(author.to_string(), email.to_string());
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    // The last index may be omitted for modes that keep it in the state file
+    // or don't scrape at all; anything after the mode belongs to the mode.
+    let mut positional = positional.into_iter().peekable();
+    if positional.peek().is_some_and(|arg| arg == "-") {
+        let mut line = String::new();
+        stdin.read_line(&mut line)?;
+        let last_index = line.trim().parse::<i32>().map_err(|err| {
+            Error::Config(format!(
+                "invalid last index on stdin '{}': {}",
+                line.trim(),
+                err
+            ))
+        })?;
+        options.last_index = Some(last_index);
+        positional.next();
+    } else if let Some(Ok(last_index)) = positional.peek().map(|arg| arg.parse::<i32>()) {
+        options.last_index = Some(last_index);
+        positional.next();
+    }
+    options.mode = positional.next().unwrap_or_else(|| "xml".to_string());
+    options.mode_args = positional.collect();
+
+    // Relative deadlines would change from one day to the next.
+    if options.deterministic {
+        options.relative_dates = false;
+    }
+
+    Ok(options)
+}
+
+fn run(options: &Options) -> Result<()> {
+    if options.mode == "multi" {
+        return run_multi(options);
+    }
+
+    if options.mode == "feeddiff" {
+        let (old, new) = match options.mode_args.as_slice() {
+            [old, new] => (fs::read_to_string(old)?, fs::read_to_string(new)?),
+            _ => return Err(Error::Config("feeddiff takes OLD and NEW".to_string())),
+        };
+        println!("{}", diff_feeds(&old, &new));
+        return Ok(());
+    }
+
+    if options.mode == "schema" {
+        println!("{}", json_schema());
+        return Ok(());
+    }
+
+    if options.mode == "watch" {
+        return run_watch(options);
+    }
+
+    let mode = options.mode.as_str();
+
+    // The state file is only used when asked for; backfill always seeds one.
+    let state_path = match (&options.state, mode) {
+        (Some(path), _) => Some(path.as_str()),
+        (None, "backfill") => Some(DEFAULT_STATE_PATH),
+        (None, _) => None,
+    };
+    let mut state = match state_path {
+        Some(path) => load_state(path)?,
+        None => Default::default(),
+    };
+    let last_index = options.last_index.unwrap_or_else(|| {
+        state
+            .boards
+            .get(BOARD_ID)
+            .map(|board_state| board_state.last_index)
+            .unwrap_or_default()
+    });
+
+    scrape(options, mode, last_index, state_path, &mut state)?;
+    Ok(())
+}
+
+fn run_watch(options: &Options) -> Result<()> {
+    static STOPPED: AtomicBool = AtomicBool::new(false);
+    ctrlc::set_handler(|| STOPPED.store(true, Ordering::SeqCst))
+        .map_err(|err| Error::Config(format!("failed to set signal handler: {}", err)))?;
+
+    watch(options, &STOPPED)
+}
+
+/// Scrapes every --interval-secs until `stopped` is set.
+fn watch(options: &Options, stopped: &AtomicBool) -> Result<()> {
+    let mode = options.mode_args.first().map_or("xml", String::as_str);
+    let state_path = options.state.as_deref();
+    let mut state = match state_path {
+        Some(path) => load_state(path)?,
+        None => Default::default(),
+    };
+    let mut last_index = options.last_index.unwrap_or_else(|| {
+        state
+            .boards
+            .get(BOARD_ID)
+            .map(|board_state| board_state.last_index)
+            .unwrap_or_default()
+    });
+
+    while !stopped.load(Ordering::SeqCst) {
+        last_index = watch_iteration(options, mode, last_index, state_path, &mut state);
+
+        let deadline = Instant::now() + Duration::from_secs(options.interval_secs);
+        while !stopped.load(Ordering::SeqCst) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    // The signal only stops the loop between iterations, so the last state
+    // write has already finished; flush once more before exiting.
+    match state_path {
+        Some(path) => save_state(path, &state),
+        None => Ok(()),
+    }
+}
+
+/// One pass of watch mode. A failed scrape is only logged so the next
+/// iteration can retry from the same index.
+fn watch_iteration(
+    options: &Options,
+    mode: &str,
+    last_index: i32,
+    state_path: Option<&str>,
+    state: &mut State,
+) -> i32 {
+    match scrape(options, mode, last_index, state_path, state) {
+        Ok(latest_index) => latest_index,
+        Err(err) => {
+            warn!("scrape failed: {}", err);
+            last_index
+        }
+    }
+}
+
+/// What a run saw, for the summary line.
+#[derive(Default)]
+struct RunSummary {
+    fetched: usize,
+    new: usize,
+    pinned: usize,
+}
+
+/// `scrape_notices`, logging exactly one summary line however the run ends.
+fn scrape(
+    options: &Options,
+    mode: &str,
+    last_index: i32,
+    state_path: Option<&str>,
+    state: &mut State,
+) -> Result<i32> {
+    let started = Instant::now();
+    let mut summary = RunSummary::default();
+    let result = scrape_notices(options, mode, last_index, state_path, state, &mut summary);
+    info!(
+        "run: fetched={} new={} pinned={} mode={} elapsed_ms={}",
+        summary.fetched,
+        summary.new,
+        summary.pinned,
+        mode,
+        started.elapsed().as_millis()
+    );
+    result
+}
+
+fn scrape_notices(
+    options: &Options,
+    mode: &str,
+    last_index: i32,
+    state_path: Option<&str>,
+    state: &mut State,
+    summary: &mut RunSummary,
+) -> Result<i32> {
+    // A HEAD request is much cheaper than fetching and parsing every page, so
+    // skip the run when the board reports it hasn't changed.
+    let last_modified = if options.check_last_modified {
+        fetch_last_modified(&options.base_url, &options.fetch).unwrap_or_else(|err| {
+            warn!("failed to check Last-Modified: {}", err);
+            None
+        })
+    } else {
+        None
+    };
+    if let Some(last_modified) = &last_modified {
+        let previous = state
+            .boards
+            .get(BOARD_ID)
+            .and_then(|board_state| board_state.last_modified.as_ref());
+        if previous == Some(last_modified) {
+            eprintln!("board not modified since {}", last_modified);
+            return Ok(last_index);
+        }
+    }
+
+    let Fetched {
+        mut notices,
+        failed_offsets,
+        row_errors,
+        fingerprint,
+    } = fetch_all(
+        &options.base_url,
+        LIMIT,
+        OFFSET,
+        options.pages,
+        &options.fetch,
+        &options.parse_config(),
+    )?;
+    if mode == "debug" {
+        print!("{}", debug_dump(&notices));
+        return Ok(last_index);
+    }
+    summary.fetched = notices.len();
+    if options.strict && !row_errors.is_empty() {
+        return Err(strict_error(&row_errors));
+    }
+    // Without a last_index from the command line or the state there is
+    // nothing to compare against, so fall back to the board's own "N" badge.
+    // Decided before check_layout, which records the board in the state.
+    let by_badge = options.new_by_badge
+        && options.last_index.is_none()
+        && !state.boards.contains_key(BOARD_ID);
+    check_layout(
+        state.boards.entry(BOARD_ID.to_string()).or_default(),
+        fingerprint,
+        options.abort_on_layout_change,
+    )?;
+    if !failed_offsets.is_empty() && !options.allow_partial {
+        return Err(Error::PartialFetch(failed_offsets));
+    }
+    if options.percent_decode {
+        for notice in notices.iter_mut() {
+            notice.title = percent_decode_title(&notice.title);
+        }
+    }
+    if options.normalize {
+        normalize_notices(&mut notices);
+    }
+    map_authors(&mut notices, &options.author_map);
+    dedup_notices(&mut notices, options.keep_pinned_duplicates);
+    sort_notices(&mut notices);
+    if let Some(top) = options.top {
+        keep_top(&mut notices, top);
+    }
+    if let Some(max_items) = max_items(options, None) {
+        notices.truncate(max_items);
+    }
+
+    if notices.is_empty() && options.fail_on_empty {
+        return Err(Error::Parse("no notices parsed".to_string()));
+    }
+
+    // An offset past the end of the board comes back as an empty table.
+    // Without a numbered notice there is nothing to compare against, so the
+    // output and the state are left as they are.
+    let latest_index = match notices.iter().find(|notice| notice.index != -1) {
+        Some(notice) => notice.index,
+        None => return Err(Error::NoNotices(OFFSET)),
+    };
+    summary.new = notices
+        .iter()
+        .filter(|notice| notice.index > last_index)
+        .count();
+    summary.pinned = notices.iter().filter(|notice| notice.index == -1).count();
+
+    if let Some(board_state) = state.boards.get(BOARD_ID) {
+        for notice in notices.iter_mut() {
+            notice.updated = board_state.is_changed(&notice_id(notice), &content_hash(notice));
+        }
+    }
+
+    if mode == "backfill" {
+        let board_state = state.boards.entry(BOARD_ID.to_string()).or_default();
+        board_state.record(
+            latest_index,
+            notices.iter().map(state_entry),
+            options.dedup_window,
+        );
+        board_state.last_modified = last_modified;
+        save_state(state_path.unwrap_or(DEFAULT_STATE_PATH), state)?;
+        write_last_index(latest_index)?;
+        return Ok(latest_index);
+    }
+
+    // A rolling window of recent changes is composed even on quiet runs.
+    let recent = options.recent_runs.is_some();
+    if recent || last_index != latest_index || notices.iter().any(|notice| notice.updated) {
+        // By default the whole page is composed once anything changed;
+        // --only-new narrows it to the notices posted since last_index.
+        if by_badge {
+            notices.retain(|notice| notice.is_new);
+        } else if options.only_new {
+            new_notices(&mut notices, last_index);
+            // A renumbered or reposted notice gets a new index but keeps its
+            // article id, so anything recorded before isn't new either.
+            if let Some(board_state) = state.boards.get(BOARD_ID) {
+                notices.retain(|notice| !board_state.has_seen(&notice_id(notice)));
+            }
+        }
+        if let (Some(runs), Some(board_state)) = (options.recent_runs, state.boards.get(BOARD_ID)) {
+            notices.retain(|notice| {
+                board_state.changed_within(&notice_id(notice), &content_hash(notice), runs)
+            });
+        }
+        // An edit alone isn't new, and an empty feed would only wipe the
+        // previous one, so leave the output and the state for the next run.
+        if options.only_new && notices.is_empty() {
+            eprintln!("new notices not found");
+            return Ok(last_index);
+        }
+
+        if options.with_body || options.preview_chars.is_some() {
+            let board_state = state.boards.entry(BOARD_ID.to_string()).or_default();
+            for notice in notices.iter_mut() {
+                notice.body = board_state
+                    .cached_body(&notice_id(notice), &content_hash(notice))
+                    .map(str::to_string);
+            }
+            fetch_bodies(
+                &mut notices,
+                &options.base_url,
+                options.max_in_flight,
+                &options.fetch,
+            );
+            for notice in &notices {
+                if let Some(body) = &notice.body {
+                    board_state.cache_body(notice_id(notice), content_hash(notice), body.clone());
+                }
+            }
+            if let Some(max_len) = options.preview_chars {
+                for notice in notices.iter_mut() {
+                    notice.body = notice
+                        .body
+                        .as_deref()
+                        .map(|body| preview_body(body, max_len));
+                }
+            } else if !options.raw_body {
+                for notice in notices.iter_mut() {
+                    notice.body = notice.body.as_deref().map(sanitize_body);
+                }
+            }
+            if let Some(max_len) = options.max_description_len {
+                for notice in notices.iter_mut() {
+                    notice.body = notice.body.as_deref().map(|body| {
+                        truncate_description(
+                            body,
+                            &notice.link,
+                            max_len,
+                            options.locale.messages().read_more,
+                        )
+                    });
+                }
+            }
+        }
+
+        if let Some(regex) = &options.filter_regex {
+            notices.retain(|notice| matches_regex(notice, regex));
+        }
+
+        if let Some(filter) = &options.filter_expr {
+            notices.retain(|notice| filter.matches(notice));
+        }
+
+        rewrite_links(&mut notices, options);
+
+        // Manual notices aren't on the board, so they skip the filters above
+        // and go on top with the pinned ones.
+        notices.splice(
+            0..0,
+            options.extra_notices.iter().map(|extra| Notice {
+                article_no: board_article_no(&options.base_url, &extra.link),
+                ..extra.clone()
+            }),
+        );
+
+        if options.emit_first_seen {
+            let board_state = state.boards.entry(BOARD_ID.to_string()).or_default();
+            mark_first_seen(&mut notices, board_state);
+        }
+
+        let now = format_date(Utc::now(), DateFormat::Rss);
+        let build_date = match state_path {
+            Some(_) => state
+                .boards
+                .entry(BOARD_ID.to_string())
+                .or_default()
+                .build_date(items_hash(&notices), now, options.stable_build_date),
+            None => now,
+        };
+
+        let new_count = if by_badge {
+            notices.len() as i32
+        } else {
+            latest_index - last_index
+        };
+        let build_date = (!options.deterministic).then_some(build_date.as_str());
+        match composer(mode, options, build_date, new_count) {
+            Some(composer) if options.split_by_category => {
+                write_by_category(&notices, composer.as_ref(), mode, options)?
+            }
+            Some(composer) => {
+                let output = compose_output(composer.as_ref(), &notices, options)?;
+                if options.publish_gist {
+                    println!("{}", publish_gist(&output, &feed_filename("feed", mode))?);
+                } else if let Some(path) = &options.output {
+                    write_output(path, output.as_bytes(), options.emit_checksum)?;
+                } else {
+                    if options.emit_checksum {
+                        warn!("--emit-checksum has no effect without --output");
+                    }
+                    print!("{}", output);
+                }
+                io::stdout().flush()?;
+                if let Some(url) = &options.post_webhook {
+                    post_webhook(url, &output, mode, &options.fetch)?;
+                }
+            }
+            None if mode == "xlsx" => {
+                let path = options
+                    .output
+                    .as_deref()
+                    .ok_or_else(|| Error::Config("xlsx mode needs --output".to_string()))?;
+                write_xlsx(
+                    &notices,
+                    path,
+                    options.emit_checksum,
+                    options.locale.messages(),
+                )?
+            }
+            None => eprintln!("unknown mode '{}'", mode),
+        }
+
+        write_last_index(latest_index)?;
+
+        // Recorded even without a state file so watch mode can compare
+        // against the previous iteration.
+        let board_state = state.boards.entry(BOARD_ID.to_string()).or_default();
+        board_state.record(
+            latest_index,
+            notices.iter().map(state_entry),
+            options.dedup_window,
+        );
+        board_state.last_modified = last_modified;
+        if let Some(state_path) = state_path {
+            save_state(state_path, state)?;
+        }
+    } else {
+        eprintln!("new notices not found");
+        if let (Some(state_path), true) = (state_path, last_modified.is_some()) {
+            state
+                .boards
+                .entry(BOARD_ID.to_string())
+                .or_default()
+                .last_modified = last_modified;
+            save_state(state_path, state)?;
+        }
+    }
+
+    Ok(latest_index)
+}
+
+pub fn main() {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("warn,dgmd_notice_rss=info"),
+    )
+    .init();
+
+    let result = parse_args(env::args()).and_then(|options| {
+        if options.quiet {
+            log::set_max_level(LevelFilter::Warn);
+        }
+        run_reporting_errors(&options)
+    });
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(err.exit_code());
+    }
+}
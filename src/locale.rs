@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+pub struct Messages {
+    pub header: &'static str,
+    pub new_notices: &'static str,
+    pub untitled: &'static str,
+    pub pinned: &'static str,
+    pub updated: &'static str,
+    pub closed: &'static str,
+    pub read_more: &'static str,
+    pub pinned_section: &'static str,
+    pub numbered_section: &'static str,
+    pub omitted: &'static str,
+    pub columns: [&'static str; 6],
+}
+
+const KO: Messages = Messages {
+    header: "미디어학과 최근 공지사항",
+    new_notices: "개의 새 공지사항",
+    untitled: "(제목 없음)",
+    pinned: "공지",
+    updated: "수정",
+    closed: "마감(지남)",
+    read_more: "더보기",
+    pinned_section: "📌 고정 공지",
+    numbered_section: "새 공지",
+    omitted: "생략된 공지",
+    columns: ["번호", "분류", "제목", "작성자", "기간", "링크"],
+};
+
+const EN: Messages = Messages {
+    header: "Department of Digital Media Recent Notices",
+    new_notices: " new notices",
+    untitled: "(untitled)",
+    pinned: "Notice",
+    updated: "Updated",
+    closed: "Closed",
+    read_more: "Read more",
+    pinned_section: "📌 Pinned",
+    numbered_section: "New",
+    omitted: "Omitted notices",
+    columns: ["No.", "Category", "Title", "Author", "Period", "Link"],
+};
+
+#[derive(Default, Clone, Copy)]
+pub enum Locale {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Locale {
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Locale::Ko => &KO,
+            Locale::En => &EN,
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        match locale {
+            "ko" => Ok(Locale::Ko),
+            "en" => Ok(Locale::En),
+            _ => Err("expected ko or en".to_string()),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Revised Romanization, syllable by syllable. Sound changes across syllable
+// boundaries are left out apart from ㄹㄹ, which is common enough in titles
+// (e.g. 설립) to be worth getting right.
+
+const INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+
+const MEDIALS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+
+const FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+const FIRST_SYLLABLE: u32 = 0xAC00;
+const LAST_SYLLABLE: u32 = 0xD7A3;
+const RIEUL: usize = 5;
+const FINAL_RIEUL: usize = 8;
+
+fn syllable(c: char) -> Option<(usize, usize, usize)> {
+    let code = c as u32;
+    if !(FIRST_SYLLABLE..=LAST_SYLLABLE).contains(&code) {
+        return None;
+    }
+    let code = (code - FIRST_SYLLABLE) as usize;
+    Some((code / 588, code % 588 / 28, code % 28))
+}
+
+/// Romanizes the Hangul runs in `text`, leaving everything else as is.
+/// Returns `None` when there is no Hangul to romanize.
+pub fn romanize(text: &str) -> Option<String> {
+    let mut romanized = String::new();
+    let mut found = false;
+    let mut previous_final = None;
+    let mut word_start = true;
+
+    for c in text.chars() {
+        let (initial, medial, last) = match syllable(c) {
+            Some(parts) => parts,
+            None => {
+                romanized.push(c);
+                previous_final = None;
+                word_start = c.is_whitespace() || c.is_ascii_punctuation();
+                continue;
+            }
+        };
+
+        let initial = match (previous_final, initial) {
+            (Some(FINAL_RIEUL), RIEUL) => "l",
+            _ => INITIALS[initial],
+        };
+        let part = format!("{}{}{}", initial, MEDIALS[medial], FINALS[last]);
+        if word_start {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                romanized.extend(first.to_uppercase());
+                romanized.push_str(chars.as_str());
+            }
+        } else {
+            romanized.push_str(&part);
+        }
+
+        found = true;
+        previous_final = Some(last);
+        word_start = false;
+    }
+
+    found.then_some(romanized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hangul_runs_are_romanized() {
+        assert_eq!(romanize("원제").as_deref(), Some("Wonje"));
+        assert_eq!(
+            romanize("수강신청 안내").as_deref(),
+            Some("Sugangsincheong Annae")
+        );
+        assert_eq!(romanize("학과 설립").as_deref(), Some("Hakgwa Seollip"));
+    }
+
+    #[test]
+    fn other_text_is_kept_as_is() {
+        assert_eq!(
+            romanize("2025학년도 AI 특강 (3/4)").as_deref(),
+            Some("2025haknyeondo AI Teukgang (3/4)")
+        );
+        assert_eq!(romanize("Career Consultant 2025"), None);
+        assert_eq!(romanize(""), None);
+    }
+}
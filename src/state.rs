@@ -0,0 +1,248 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+pub const DEFAULT_STATE_PATH: &str = "state.json";
+pub const DEFAULT_DEDUP_WINDOW: usize = 500;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct State {
+    pub boards: BTreeMap<String, BoardState>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct BoardState {
+    pub last_index: i32,
+    #[serde(default)]
+    pub seen: Vec<String>,
+    #[serde(default)]
+    pub hashes: BTreeMap<String, String>,
+    #[serde(default)]
+    pub items_hash: Option<String>,
+    #[serde(default)]
+    pub last_build_date: Option<String>,
+    #[serde(default)]
+    pub first_seen: BTreeMap<String, String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub runs: u64,
+    #[serde(default)]
+    pub last_changed: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub bodies: BTreeMap<String, CachedBody>,
+    #[serde(default)]
+    pub layout_fingerprint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CachedBody {
+    pub hash: String,
+    pub body: String,
+}
+
+impl BoardState {
+    pub fn record(
+        &mut self,
+        last_index: i32,
+        entries: impl Iterator<Item = (String, String)>,
+        window: usize,
+    ) {
+        self.last_index = last_index;
+        self.runs += 1;
+
+        // Entries come newest first; push them oldest first so the newest ids
+        // end up at the back and the front can be trimmed.
+        let entries = entries.collect::<Vec<_>>();
+        for (id, hash) in entries.into_iter().rev() {
+            if !self.seen.contains(&id) {
+                self.seen.push(id.clone());
+            }
+            if self.hashes.get(&id) != Some(&hash) {
+                self.last_changed.insert(id.clone(), self.runs);
+            }
+            self.hashes.insert(id, hash);
+        }
+
+        if self.seen.len() > window {
+            let dropped = self.seen.len() - window;
+            for id in self.seen.drain(..dropped) {
+                self.hashes.remove(&id);
+                self.first_seen.remove(&id);
+                self.last_changed.remove(&id);
+            }
+        }
+        let hashes = &self.hashes;
+        self.bodies.retain(|id, _| hashes.contains_key(id));
+    }
+
+    /// The detail body fetched for the notice, as long as its row hasn't
+    /// changed since.
+    pub fn cached_body(&self, id: &str, hash: &str) -> Option<&str> {
+        self.bodies
+            .get(id)
+            .filter(|cached| cached.hash == hash)
+            .map(|cached| cached.body.as_str())
+    }
+
+    pub fn cache_body(&mut self, id: String, hash: String, body: String) {
+        self.bodies.insert(id, CachedBody { hash, body });
+    }
+
+    pub fn first_seen(&mut self, id: String, now: &str) -> String {
+        self.first_seen
+            .entry(id)
+            .or_insert_with(|| now.to_string())
+            .clone()
+    }
+
+    pub fn build_date(&mut self, items_hash: String, now: String, stable: bool) -> String {
+        let unchanged = self.items_hash.as_ref() == Some(&items_hash);
+        let build_date = match &self.last_build_date {
+            Some(last_build_date) if stable && unchanged => last_build_date.clone(),
+            _ => now,
+        };
+
+        self.items_hash = Some(items_hash);
+        self.last_build_date = Some(build_date.clone());
+        build_date
+    }
+
+    /// Whether the notice was added or edited in this run or one of the
+    /// previous `runs - 1` recorded runs.
+    pub fn changed_within(&self, id: &str, hash: &str, runs: u64) -> bool {
+        let current = self.runs + 1;
+        self.hashes.get(id).map(String::as_str) != Some(hash)
+            || self
+                .last_changed
+                .get(id)
+                .is_some_and(|&changed| changed + runs > current)
+    }
+
+    /// Whether the notice was recorded in one of the last `window` ids.
+    pub fn has_seen(&self, id: &str) -> bool {
+        self.seen.iter().any(|seen| seen == id)
+    }
+
+    pub fn is_changed(&self, id: &str, hash: &str) -> bool {
+        self.hashes.get(id).is_some_and(|previous| previous != hash)
+    }
+}
+
+pub fn load_state(path: &str) -> Result<State> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|err| Error::State(format!("invalid state file '{}': {}", path, err))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(State::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn save_state(path: &str, state: &State) -> Result<()> {
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|err| Error::State(format!("failed to serialize state: {}", err)))?;
+    // Write next to the target and rename over it so an interrupted write
+    // never leaves a truncated state file behind.
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        entries
+            .iter()
+            .map(|(id, hash)| (id.to_string(), hash.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn a_changed_hash_marks_the_notice_changed() {
+        let mut board_state = BoardState::default();
+        board_state.record(2, entries(&[("2", "a"), ("1", "b")]), 500);
+
+        assert!(!board_state.is_changed("2", "a"));
+        assert!(board_state.is_changed("2", "edited"));
+        // A notice that was never recorded is new, not changed.
+        assert!(!board_state.is_changed("3", "c"));
+    }
+
+    #[test]
+    fn stable_build_date_only_moves_when_the_items_change() {
+        let mut board_state = BoardState::default();
+        let first = "Mon, 03 Mar 2025 09:00:00 +0900".to_string();
+        let later = "Tue, 04 Mar 2025 09:00:00 +0900".to_string();
+
+        assert_eq!(
+            board_state.build_date("items".to_string(), first.clone(), true),
+            first
+        );
+        assert_eq!(
+            board_state.build_date("items".to_string(), later.clone(), true),
+            first
+        );
+        assert_eq!(
+            board_state.build_date("more items".to_string(), later.clone(), true),
+            later
+        );
+    }
+
+    #[test]
+    fn build_date_is_always_now_without_the_option() {
+        let mut board_state = BoardState::default();
+        board_state.build_date("items".to_string(), "then".to_string(), false);
+        assert_eq!(
+            board_state.build_date("items".to_string(), "now".to_string(), false),
+            "now"
+        );
+    }
+
+    #[test]
+    fn seen_ids_are_trimmed_to_the_window_keeping_the_newest() {
+        let mut board_state = BoardState::default();
+        // Newest first, as scraped.
+        let ids = (0..600)
+            .rev()
+            .map(|id| (id.to_string(), "hash".to_string()));
+        board_state.record(599, ids, 500);
+
+        assert_eq!(board_state.seen.len(), 500);
+        assert_eq!(board_state.seen.first().map(String::as_str), Some("100"));
+        assert_eq!(board_state.seen.last().map(String::as_str), Some("599"));
+        assert!(board_state.has_seen("599") && board_state.has_seen("100"));
+        assert!(!board_state.has_seen("99"));
+        assert!(!board_state.hashes.contains_key("99"));
+        assert_eq!(board_state.hashes.len(), 500);
+    }
+
+    #[test]
+    fn later_runs_push_out_the_oldest_ids() {
+        let mut board_state = BoardState::default();
+        board_state.record(3, entries(&[("3", "c"), ("2", "b"), ("1", "a")]), 3);
+        board_state.record(5, entries(&[("5", "e"), ("4", "d")]), 3);
+
+        assert_eq!(board_state.seen, ["3", "4", "5"]);
+    }
+
+    #[test]
+    fn changed_within_covers_the_last_n_runs() {
+        let mut board_state = BoardState::default();
+        board_state.record(2, entries(&[("2", "b"), ("1", "a")]), 500);
+        board_state.record(3, entries(&[("3", "c"), ("2", "b"), ("1", "a")]), 500);
+
+        // The third run adds 4 and edits 2; 3 came in the run before.
+        let within = |id: &str, hash: &str| board_state.changed_within(id, hash, 2);
+        assert!(within("4", "d"));
+        assert!(within("2", "b2"));
+        assert!(within("3", "c"));
+        assert!(!within("1", "a"));
+        assert!(!board_state.changed_within("3", "c", 1));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{long_version, user_agent, version};
+
+#[test]
+fn version_carries_the_crate_version_and_build_sha() {
+    assert_eq!(version(), env!("CARGO_PKG_VERSION"));
+    match option_env!("GIT_SHA") {
+        Some(sha) => assert_eq!(long_version(), format!("{}+{}", version(), sha)),
+        None => assert_eq!(long_version(), version()),
+    }
+    assert!(user_agent().ends_with(&format!("/{})", version())));
+}
+
+fn args_with_stdin(args: &[&str], stdin: &str) -> crate::error::Result<crate::Options> {
+    let argv = ["dgmd-notice-rss"]
+        .iter()
+        .chain(args)
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    crate::parse_args_with_stdin(argv.into_iter(), &mut stdin.as_bytes())
+}
+
+#[test]
+fn a_dash_reads_last_index_from_stdin() {
+    let options = args_with_stdin(&["-", "md"], "42\n").ok().unwrap();
+    assert_eq!(options.last_index, Some(42));
+    assert_eq!(options.mode, "md");
+
+    let options = args_with_stdin(&["1220"], "42\n").ok().unwrap();
+    assert_eq!(options.last_index, Some(1220));
+    assert_eq!(options.mode, "xml");
+}
+
+#[test]
+fn a_bad_last_index_on_stdin_is_a_config_error() {
+    let err = args_with_stdin(&["-"], "forty-two\n").err().unwrap();
+    assert_eq!(err.exit_code(), 6);
+    assert_eq!(
+        err.to_string(),
+        "config error: invalid last index on stdin 'forty-two': invalid digit found in string"
+    );
+}
@@ -0,0 +1,838 @@
+use super::{compose, sample_notices};
+use crate::version;
+
+#[test]
+fn rss_lists_every_notice_in_order() {
+    let xml = compose("xml", &sample_notices(), &[]);
+
+    assert_eq!(xml.matches("<item>").count(), 5);
+    let first = xml.find("학과사무실 운영 시간 안내").unwrap();
+    let last = xml.find("졸업작품전시회 개최").unwrap();
+    assert!(first < last);
+    assert!(xml.contains("<title>2025학년도 1학기 교내장학 신청 안내 &amp; 서류 제출</title>"));
+}
+
+fn channel_link(xml: &str) -> &str {
+    let start = xml.find("<link>").unwrap() + "<link>".len();
+    let end = start + xml[start..].find("</link>").unwrap();
+    &xml[start..end]
+}
+
+#[test]
+fn rss_channel_link_defaults_to_the_board_page() {
+    let xml = compose("xml", &sample_notices(), &[]);
+    assert_eq!(
+        channel_link(&xml),
+        "https://media.ajou.ac.kr/media/board/board01.jsp"
+    );
+}
+
+#[test]
+fn rss_channel_link_follows_the_human_url() {
+    let xml = compose(
+        "xml",
+        &sample_notices(),
+        &["--human-url", "https://example.com/notices"],
+    );
+    assert_eq!(channel_link(&xml), "https://example.com/notices");
+}
+
+#[test]
+fn feed_url_and_human_url_land_in_their_own_elements() {
+    let args = [
+        "--feed-url",
+        "https://example.com/feed.xml",
+        "--human-url",
+        "https://example.com/notices",
+        "--emit-comments",
+    ];
+
+    let xml = compose("xml", &sample_notices(), &args);
+    assert_eq!(channel_link(&xml), "https://example.com/notices");
+    assert!(xml.contains(
+        "<atom:link href=\"https://example.com/feed.xml\" rel=\"self\" type=\"application/rss+xml\"/>"
+    ));
+    assert!(xml.contains("<comments>https://example.com/notices?mode=view&amp;articleNo="));
+
+    let feed = compose("jsonfeed", &sample_notices(), &args);
+    let feed = serde_json::from_str::<serde_json::Value>(&feed).unwrap();
+    assert_eq!(feed["feed_url"], "https://example.com/feed.xml");
+    assert_eq!(feed["home_page_url"], "https://example.com/notices");
+}
+
+#[test]
+fn json_feed_has_the_required_keys() {
+    let feed = compose("jsonfeed", &sample_notices(), &[]);
+    let feed = serde_json::from_str::<serde_json::Value>(&feed).unwrap();
+
+    assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+    for key in ["title", "home_page_url", "feed_url"] {
+        assert!(feed[key].is_string(), "missing {}", key);
+    }
+    let items = feed["items"].as_array().unwrap();
+    assert_eq!(items.len(), 5);
+    for item in items {
+        for key in ["id", "url", "title", "content_text"] {
+            assert!(item[key].is_string(), "item is missing {}", key);
+        }
+    }
+}
+
+#[test]
+fn md_marks_pinned_notices() {
+    let md = compose("md", &sample_notices(), &[]);
+    assert!(md.contains("📌 [공지] 학과사무실 운영 시간 안내"));
+}
+
+#[test]
+fn md_without_pinned_marker_keeps_pinned_notices() {
+    let md = compose("md", &sample_notices(), &["--no-pinned-marker"]);
+    assert!(!md.contains('📌'));
+    assert!(md.contains("**[[공지] 학과사무실 운영 시간 안내]("));
+}
+
+#[test]
+fn rss_author_uses_the_mapped_email() {
+    let xml = compose(
+        "xml",
+        &sample_notices(),
+        &["--author-email", "디지털미디어학과=dgmd@ajou.ac.kr"],
+    );
+
+    assert!(xml.contains("<author>dgmd@ajou.ac.kr (디지털미디어학과)</author>"));
+    assert!(xml.contains("<dc:creator>소프트웨어융합대학 교학팀</dc:creator>"));
+    assert!(!xml.contains("<dc:creator>디지털미디어학과</dc:creator>"));
+}
+
+#[test]
+fn rss_author_without_a_mapping_is_dc_creator() {
+    let xml = compose("xml", &sample_notices(), &[]);
+    assert!(!xml.contains("<author>"));
+    assert!(xml.contains("<dc:creator>디지털미디어학과</dc:creator>"));
+}
+
+#[test]
+fn iso_dates_are_utc_from_kst_midnight() {
+    assert_eq!(
+        crate::iso_date("2025-03-31").as_deref(),
+        Some("2025-03-30T15:00:00Z")
+    );
+    assert_eq!(
+        crate::iso_date("2025년 03월 31일").as_deref(),
+        Some("2025-03-30T15:00:00Z")
+    );
+    assert_eq!(crate::iso_date("상시"), None);
+}
+
+#[test]
+fn json_has_iso_deadlines_only_when_parseable() {
+    let mut notices = sample_notices();
+    notices[0].expired_at = "상시".to_string();
+    let json = compose("json", &notices, &[]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+
+    assert!(json[0].get("iso_expired_at").is_none());
+    assert_eq!(json[0]["expired_at"], "상시");
+    assert_eq!(json[2]["iso_expired_at"], "2025-03-30T15:00:00Z");
+}
+
+#[test]
+fn json_includes_the_badge_flags() {
+    let json = compose("json", &sample_notices(), &[]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+
+    assert_eq!(json[0]["is_important"], true);
+    assert_eq!(json[2]["is_new"], true);
+    assert_eq!(json[2]["has_attachment"], true);
+    assert_eq!(json[3]["is_new"], false);
+}
+
+#[test]
+fn truncation_keeps_grapheme_clusters_whole() {
+    use crate::truncate_graphemes;
+
+    assert_eq!(
+        truncate_graphemes("교내장학 신청 안내", 4, "…"),
+        "교내장학…"
+    );
+    assert_eq!(truncate_graphemes("짧은 제목", 10, "…"), "짧은 제목");
+    // Exactly at the limit nothing is cut, one past it is.
+    assert_eq!(truncate_graphemes("교내장학", 4, "…"), "교내장학");
+    assert_eq!(truncate_graphemes("교내장학금", 4, "…"), "교내장학…");
+    assert_eq!(truncate_graphemes("교내장학", 0, ""), "");
+    // Decomposed Hangul and emoji with modifiers are single clusters.
+    let decomposed = "\u{1112}\u{1161}\u{11ab}\u{1100}\u{1173}\u{11af}";
+    assert_eq!(
+        truncate_graphemes(decomposed, 1, "…"),
+        "\u{1112}\u{1161}\u{11ab}…"
+    );
+    assert_eq!(truncate_graphemes("👍🏽👍🏽", 1, ""), "👍🏽");
+}
+
+#[test]
+fn max_title_len_truncates_md_and_commit_titles_only() {
+    let args = ["--max-title-len", "9"];
+    let truncated = "2025학년도 1…";
+
+    assert!(compose("md", &sample_notices(), &args).contains(truncated));
+    assert!(compose("cm", &sample_notices(), &args).contains(truncated));
+    let xml = compose("xml", &sample_notices(), &args);
+    assert!(!xml.contains(truncated));
+    assert!(xml.contains("2025학년도 1학기 교내장학 신청 안내 &amp; 서류 제출"));
+}
+
+#[test]
+fn text_digest_is_plain() {
+    let notices = sample_notices();
+    let text = compose("text", &notices[2..3], &[]);
+
+    assert_eq!(
+        text,
+        format!(
+            "미디어학과 최근 공지사항\n\n\
+             • 2025학년도 1학기 교내장학 신청 안내 & 서류 제출\n  \
+             [장학] 소프트웨어융합대학 교학팀 ~2025-03-31\n  \
+             {}\n",
+            notices[2].link
+        )
+    );
+    let text = compose("text", &notices, &[]);
+    assert!(!text.contains("&amp;") && !text.contains('<') && !text.contains("**"));
+    assert_eq!(text.matches("• ").count(), 5);
+}
+
+fn days_from_today(days: i64) -> String {
+    let today = chrono::Utc::now().with_timezone(&crate::kst()).date_naive();
+    (today + chrono::Duration::days(days))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[test]
+fn relative_deadlines_count_days_in_kst() {
+    use crate::{format_deadline, Locale};
+    let messages = Locale::Ko.messages();
+
+    assert_eq!(format_deadline(&days_from_today(3), true, messages), "~D-3");
+    assert_eq!(format_deadline(&days_from_today(1), true, messages), "~D-1");
+    assert_eq!(
+        format_deadline(&days_from_today(0), true, messages),
+        "~D-DAY"
+    );
+    assert_eq!(
+        format_deadline(&days_from_today(-1), true, messages),
+        "마감(지남)"
+    );
+    assert_eq!(format_deadline("상시", true, messages), "~상시");
+    assert_eq!(
+        format_deadline("2025-03-31", false, messages),
+        "~2025-03-31"
+    );
+}
+
+#[test]
+fn relative_dates_apply_to_md_and_text_but_not_rss() {
+    let mut notices = sample_notices();
+    notices[2].expired_at = days_from_today(3);
+    let args = ["--relative-dates"];
+
+    assert!(compose("md", &notices, &args).contains("(~D-3)"));
+    assert!(compose("text", &notices, &args).contains(" ~D-3\n"));
+    let xml = compose("xml", &notices, &args);
+    assert!(!xml.contains("D-3"));
+    assert!(xml.contains(&format!("(~{})", days_from_today(3))));
+}
+
+fn only_line_ending(text: &str, crlf: bool) -> bool {
+    let lines = text.matches('\n').count();
+    let crlfs = text.matches("\r\n").count();
+    lines > 0 && if crlf { crlfs == lines } else { crlfs == 0 }
+}
+
+#[test]
+fn line_ending_applies_to_every_mode() {
+    for mode in ["xml", "json", "jsonfeed", "md", "html", "text", "cm"] {
+        let lf = compose(mode, &sample_notices(), &[]);
+        let crlf = compose(mode, &sample_notices(), &["--line-ending", "crlf"]);
+        assert!(only_line_ending(&lf, false), "{}", mode);
+        assert!(only_line_ending(&crlf, true), "{}", mode);
+        assert_eq!(crlf.replace("\r\n", "\n"), lf, "{}", mode);
+    }
+}
+
+#[test]
+fn line_ending_conversion_normalizes_mixed_input() {
+    use crate::{convert_line_endings, LineEnding};
+
+    assert_eq!(
+        convert_line_endings("a\r\nb\nc", LineEnding::Crlf),
+        "a\r\nb\r\nc"
+    );
+    assert_eq!(convert_line_endings("a\r\nb\nc", LineEnding::Lf), "a\nb\nc");
+}
+
+#[test]
+fn emoji_config_labels_the_configured_categories() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("emoji.toml");
+    std::fs::write(
+        &config,
+        "\"장학\" = \"🎓\"\n\"행사\" = \"축제\"\n\"취업\" = 3\n",
+    )
+    .unwrap();
+    let args = ["--emoji-config", config.to_str().unwrap()];
+    let notices = sample_notices();
+
+    let md = compose("md", &notices, &args);
+    assert!(md.contains("🎓 장학"), "{}", md);
+    let text = compose("text", &notices, &args);
+    assert!(text.contains("[🎓 장학]"), "{}", text);
+
+    // Entries that aren't a single emoji are dropped, not fatal.
+    assert!(!text.contains("축제 행사"), "{}", text);
+    assert!(text.contains("[행사]"), "{}", text);
+    assert!(text.contains("[취업]"), "{}", text);
+}
+
+#[test]
+fn index_in_title_prefixes_rss_and_md_titles() {
+    let notices = sample_notices();
+
+    let xml = compose("xml", &notices, &["--index-in-title"]);
+    assert!(xml.contains("<title>[#1220] "), "{}", xml);
+    assert!(xml.contains("<title>[공지] 2025학년도 1학기 수강신청 안내</title>"));
+    let md = compose("md", &notices, &["--index-in-title"]);
+    assert!(md.contains("[#1221] 2025학년도 1학기 교내장학"), "{}", md);
+    assert!(
+        md.contains("[공지] 2025학년도 1학기 수강신청 안내"),
+        "{}",
+        md
+    );
+
+    let plain = compose("xml", &notices, &[]);
+    assert!(!plain.contains("[#1220]"));
+    assert!(plain.contains("<title>2025학년도 1학기 수강신청 안내</title>"));
+}
+
+#[test]
+fn compact_json_round_trips_to_the_pretty_value() {
+    let notices = sample_notices();
+    for mode in ["json", "jsonfeed"].iter() {
+        let pretty = compose(mode, &notices, &[]);
+        let compact = compose(mode, &notices, &["--compact-json"]);
+
+        assert!(pretty.trim_end().contains("\n  "), "{}", pretty);
+        assert!(!compact.trim_end().contains('\n'), "{}", compact);
+        assert!(!compact.contains(": "), "{}", compact);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+        );
+    }
+}
+
+#[test]
+fn commit_message_bullets_wrap_with_a_hanging_indent() {
+    let notices = sample_notices();
+
+    let message = compose("cm", &notices[2..3], &["--wrap-width", "24"]);
+    assert_eq!(
+        message,
+        "dist: 0개의 새 공지사항\n\n\
+         * 2025학년도 1학기\n  \
+         교내장학 신청 안내 &\n  \
+         서류 제출\n"
+    );
+
+    let unwrapped = compose("cm", &notices[2..3], &[]);
+    assert!(unwrapped.contains("* 2025학년도 1학기 교내장학 신청 안내 & 서류 제출\n"));
+}
+
+#[test]
+fn json_schema_describes_the_json_output() {
+    let schema = serde_json::from_str::<serde_json::Value>(&crate::json_schema()).unwrap();
+
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(schema["type"], "array");
+    assert_eq!(schema["items"]["$ref"], "#/$defs/Notice");
+    let notice = &schema["$defs"]["Notice"];
+    for key in ["index", "title", "author", "category", "link", "expired_at"].iter() {
+        assert!(notice["required"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!(key)));
+    }
+
+    // Every key the json mode emits is described.
+    let properties = notice["properties"].as_object().unwrap();
+    let json = compose("json", &sample_notices(), &[]);
+    for item in serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap() {
+        for key in item.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "{} is not in the schema", key);
+        }
+    }
+}
+
+#[test]
+fn guid_permalink_picks_the_guid_kind() {
+    let notices = sample_notices();
+    let link = "http://media.ajou.ac.kr/media/board/notice.do?mode=view&amp;articleNo=341221";
+
+    for xml in [
+        compose("xml", &notices, &[]),
+        compose("xml", &notices, &["--guid-permalink", "false"]),
+    ]
+    .iter()
+    {
+        assert!(
+            xml.contains("<guid isPermaLink=\"false\">341221</guid>"),
+            "{}",
+            xml
+        );
+        assert!(!xml.contains("isPermaLink=\"true\""));
+    }
+
+    let xml = compose("xml", &notices, &["--guid-permalink", "true"]);
+    assert!(
+        xml.contains(&format!("<guid isPermaLink=\"true\">{}</guid>", link)),
+        "{}",
+        xml
+    );
+    assert!(!xml.contains("isPermaLink=\"false\""));
+}
+
+#[test]
+fn guid_permalink_needs_a_boolean() {
+    let err = super::options_error(&["--guid-permalink", "yes"]);
+    assert_eq!(err.exit_code(), 6);
+}
+
+#[test]
+fn full_puts_the_body_in_cdata_and_keeps_the_summary() {
+    let mut notices = sample_notices();
+    notices[2].body =
+        Some("<p>신청 기간: 3월 4일 ~ 3월 31일 <strong>&amp; 서류</strong></p>".to_string());
+
+    let xml = compose("xml", &notices, &["--full"]);
+
+    super::assert_well_formed(&xml);
+    assert!(xml.contains(
+        "<description><![CDATA[<p>신청 기간: 3월 4일 ~ 3월 31일 <strong>&amp; 서류</strong></p>]]></description>"
+    ), "{}", xml);
+    assert!(
+        xml.contains("<dc:description>[장학] - 소프트웨어융합대학 교학팀"),
+        "{}",
+        xml
+    );
+    // Notices without a body keep the summary as their description.
+    assert!(xml.contains("<description>[취업] - "), "{}", xml);
+
+    let summary = compose("xml", &notices, &[]);
+    assert!(!summary.contains("CDATA"));
+}
+
+#[test]
+fn cdata_bodies_cannot_close_the_section_early() {
+    let mut notices = sample_notices();
+    notices[2].body = Some("<p>a]]>b</p>".to_string());
+
+    super::assert_well_formed(&compose("xml", &notices, &["--full"]));
+}
+
+#[test]
+fn emit_comments_links_the_view_page() {
+    let notices = sample_notices();
+
+    let xml = compose("xml", &notices, &["--emit-comments"]);
+    assert!(xml.contains(
+        "<comments>http://media.ajou.ac.kr/media/board/notice.do?mode=view&amp;articleNo=341221</comments>"
+    ), "{}", xml);
+    assert_eq!(xml.matches("<comments>").count(), notices.len());
+
+    assert!(!compose("xml", &notices, &[]).contains("<comments>"));
+}
+
+#[test]
+fn json_feed_items_carry_raw_and_iso_deadlines() {
+    let mut notices = sample_notices();
+    notices[0].expired_at = "상시".to_string();
+    let feed = compose("jsonfeed", &notices, &[]);
+    let feed = serde_json::from_str::<serde_json::Value>(&feed).unwrap();
+    let items = feed["items"].as_array().unwrap();
+
+    assert_eq!(items[0]["_expired_at"], "상시");
+    assert!(items[0].get("_iso_expired_at").is_none());
+    assert_eq!(items[3]["_expired_at"], "2025-12-30");
+    assert_eq!(items[3]["_iso_expired_at"], "2025-12-29T15:00:00Z");
+}
+
+#[test]
+fn every_composer_renders_the_fixture() {
+    use crate::{compose_output, composer};
+
+    let notices = {
+        let (mut notices, _) =
+            crate::parse_html(&super::fixture("board.html"), crate::BASE_URL, "(untitled)");
+        crate::dedup_notices(&mut notices, false);
+        notices
+    };
+    let options = super::options(&[]);
+
+    for mode in ["xml", "json", "jsonfeed", "md", "html", "text", "cm"].iter() {
+        let composer = composer(mode, &options, None, 2).unwrap();
+        let output = compose_output(composer.as_ref(), &notices, &options).unwrap();
+        assert!(
+            output.contains("2025학년도 1학기 수강신청 안내"),
+            "{} output: {}",
+            mode,
+            output
+        );
+    }
+    assert!(composer("yaml", &options, None, 2).is_none());
+}
+
+#[test]
+fn split_author_fills_department_and_name_in_json_only() {
+    let mut notices = sample_notices();
+    notices[3].author = "디지털미디어학과 김조교".to_string();
+    notices[4].author = "학생지원팀/박주무관".to_string();
+
+    let json = compose("json", &notices, &["--split-author"]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(json[3]["author"], "디지털미디어학과 김조교");
+    assert_eq!(json[3]["department"], "디지털미디어학과");
+    assert_eq!(json[3]["author_name"], "김조교");
+    // A single word has nothing to split.
+    assert!(json[0].get("department").is_none());
+
+    let json = compose("json", &notices, &["--author-delimiter", "/"]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(json[4]["department"], "학생지원팀");
+    assert_eq!(json[4]["author_name"], "박주무관");
+
+    let plain = compose("json", &notices, &[]);
+    assert!(!plain.contains("\"department\""));
+    let xml = compose("xml", &notices, &["--split-author"]);
+    assert!(xml.contains("<dc:creator>디지털미디어학과 김조교</dc:creator>"));
+}
+
+fn zip_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> String {
+    use std::io::Read;
+
+    let mut contents = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    contents
+}
+
+#[test]
+fn xlsx_has_one_sheet_with_a_header_and_a_row_per_notice() {
+    let notices = sample_notices();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notices.xlsx");
+
+    crate::write_xlsx(
+        &notices,
+        path.to_str().unwrap(),
+        false,
+        crate::Locale::Ko.messages(),
+    )
+    .unwrap();
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let workbook = zip_entry(&mut archive, "xl/workbook.xml");
+    assert_eq!(workbook.matches("<sheet ").count(), 1);
+    let sheet = zip_entry(&mut archive, "xl/worksheets/sheet1.xml");
+    assert_eq!(sheet.matches("<row ").count(), notices.len() + 1);
+    assert_eq!(sheet.matches("<hyperlink ").count(), notices.len());
+    assert!(sheet.contains("<pane ySplit=\"1\""), "{}", sheet);
+    let strings = zip_entry(&mut archive, "xl/sharedStrings.xml");
+    assert!(strings.contains("2025학년도 1학기 수강신청 안내"));
+}
+
+#[test]
+fn locale_en_switches_the_fixed_strings_only() {
+    let notices = sample_notices();
+
+    let md = compose("md", &notices, &["--locale", "en"]);
+    assert!(
+        md.starts_with(r"# Department of Digital Media Recent Notices\n"),
+        "{}",
+        md
+    );
+    assert!(md.contains("2025학년도 1학기 수강신청 안내"));
+    let cm = compose("cm", &notices, &["--locale", "en"]);
+    assert!(cm.starts_with("dist: 0 new notices\n"), "{}", cm);
+    let md = compose("md", &notices, &["--locale", "en", "--index-in-title"]);
+    assert!(
+        md.contains("[Notice] 2025학년도 1학기 수강신청 안내"),
+        "{}",
+        md
+    );
+
+    let md = compose("md", &notices, &[]);
+    assert!(md.starts_with(r"# 미디어학과 최근 공지사항\n"), "{}", md);
+    assert_eq!(super::options_error(&["--locale", "fr"]).exit_code(), 6);
+}
+
+#[test]
+fn each_format_escapes_fields_exactly_once() {
+    let notices = vec![super::notice(1221, "R&D <세미나> 안내")];
+
+    let xml = compose("xml", &notices, &[]);
+    assert!(
+        xml.contains("<title>R&amp;D &lt;세미나&gt; 안내</title>"),
+        "{}",
+        xml
+    );
+    assert!(!xml.contains("&amp;amp;"));
+
+    let html = compose("html", &notices, &[]);
+    assert!(html.contains("R&amp;D &lt;세미나&gt; 안내"), "{}", html);
+    assert!(!html.contains("&amp;amp;"));
+
+    let md = compose("md", &notices, &[]);
+    assert!(md.contains("R&amp;D &lt;세미나&gt; 안내"), "{}", md);
+    assert!(!md.contains("&amp;amp;"));
+
+    let json = compose("json", &notices, &[]);
+    let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(parsed[0]["title"], "R&D <세미나> 안내");
+
+    let text = compose("text", &notices, &[]);
+    assert!(text.contains("R&D <세미나> 안내"), "{}", text);
+}
+
+#[test]
+fn separate_pinned_splits_md_and_text_into_two_sections() {
+    let md = compose("md", &sample_notices(), &["--separate-pinned"]);
+    let pinned = md.find(r"## 📌 고정 공지\n\n").unwrap();
+    let numbered = md.find(r"## 새 공지\n\n").unwrap();
+    assert!(pinned < numbered);
+    for title in &["학과사무실 운영 시간 안내", "수강신청 안내"] {
+        let at = md.find(title).unwrap();
+        assert!(pinned < at && at < numbered, "{}", title);
+    }
+    for title in &["교내장학 신청 안내", "취업/진로상담", "졸업작품전시회 개최"]
+    {
+        assert!(md.find(title).unwrap() > numbered, "{}", title);
+    }
+
+    let text = compose("text", &sample_notices(), &["--separate-pinned"]);
+    let pinned = text.find("📌 고정 공지\n\n• ").unwrap();
+    let numbered = text.find("새 공지\n\n• ").unwrap();
+    assert!(pinned < text.find("수강신청 안내").unwrap());
+    assert!(numbered < text.find("교내장학 신청 안내").unwrap());
+
+    let xml = compose("xml", &sample_notices(), &["--separate-pinned"]);
+    assert!(!xml.contains("고정 공지"));
+}
+
+#[test]
+fn max_output_bytes_truncates_or_errors() {
+    let full = compose("xml", &sample_notices(), &[]);
+    let limit = (full.len() * 2 / 3).to_string();
+
+    let xml = compose("xml", &sample_notices(), &["--max-output-bytes", &limit]);
+    assert!(xml.len() <= full.len() * 2 / 3, "{} bytes", xml.len());
+    let items = xml.matches("<item>").count();
+    assert!(items > 1 && items < 5, "{} items", items);
+    assert!(xml.contains(&format!("<title>생략된 공지 ({})</title>", 6 - items)));
+    assert!(!xml.contains("졸업작품전시회"));
+
+    let options = super::options(&["--max-output-bytes", &limit, "--max-output-error"]);
+    let composer = crate::composer("xml", &options, None, 0).unwrap();
+    let err = crate::compose_output(composer.as_ref(), &sample_notices(), &options)
+        .err()
+        .unwrap();
+    assert_eq!(err.exit_code(), 11);
+}
+
+/// The text of each `<tag>` element in `xml`, in document order.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        // Skip longer names sharing the prefix, e.g. <itemX> for <item>.
+        if !rest.starts_with(['>', ' ']) {
+            continue;
+        }
+        let body = &rest[rest.find('>').unwrap() + 1..];
+        let end = body
+            .find(&close)
+            .unwrap_or_else(|| panic!("unclosed {}", open));
+        found.push(&body[..end]);
+        rest = &body[end..];
+    }
+    found
+}
+
+/// Checks the RSS 2.0 rules readers rely on beyond well-formedness: the
+/// root and its version, every used namespace declared, the required
+/// channel elements, and a title, absolute link and guid on every item.
+fn assert_conforming_rss(xml: &str, items: usize) {
+    super::assert_well_formed(xml);
+    assert!(xml.starts_with("<rss version=\"2.0\""), "{}", xml);
+    for prefix in &["dc", "atom"] {
+        if xml.contains(&format!("<{}:", prefix)) {
+            assert!(
+                xml.contains(&format!("xmlns:{}=", prefix)),
+                "{} undeclared",
+                prefix
+            );
+        }
+    }
+
+    let channels = elements(xml, "channel");
+    assert_eq!(channels.len(), 1);
+    let channel = channels[0];
+    let head = &channel[..channel.find("<item>").unwrap_or(channel.len())];
+    for tag in &["title", "link", "description"] {
+        let values = elements(head, tag);
+        assert_eq!(values.len(), 1, "channel <{}> in {}", tag, head);
+        assert!(!values[0].trim().is_empty(), "empty channel <{}>", tag);
+    }
+
+    let entries = elements(channel, "item");
+    assert_eq!(entries.len(), items);
+    for item in entries {
+        let titles = elements(item, "title");
+        assert_eq!(titles.len(), 1, "{}", item);
+        assert!(!titles[0].trim().is_empty(), "{}", item);
+        let links = elements(item, "link");
+        assert_eq!(links.len(), 1, "{}", item);
+        assert!(links[0].starts_with("http"), "{}", item);
+        assert_eq!(elements(item, "guid").len(), 1, "{}", item);
+    }
+}
+
+#[test]
+fn rss_output_conforms_to_rss_2_0() {
+    assert_conforming_rss(&compose("xml", &sample_notices(), &[]), 5);
+    assert_conforming_rss(
+        &compose(
+            "xml",
+            &sample_notices(),
+            &["--full", "--guid-permalink", "true", "--emit-comments"],
+        ),
+        5,
+    );
+    assert_conforming_rss(&compose("xml", &[], &[]), 0);
+
+    let options = super::options(&["--feed-url", "https://example.com/feed.xml"]);
+    let composer =
+        crate::composer("xml", &options, Some("Mon, 03 Mar 2025 09:00:00 +0900"), 0).unwrap();
+    let xml = crate::compose_output(composer.as_ref(), &sample_notices(), &options).unwrap();
+    assert_conforming_rss(&xml, 5);
+    let build_date = elements(&xml, "lastBuildDate");
+    assert!(chrono::DateTime::parse_from_rfc2822(build_date[0]).is_ok());
+}
+
+#[test]
+fn the_generator_names_the_crate_version() {
+    let xml = compose("xml", &sample_notices(), &[]);
+    assert!(xml.contains(&format!(
+        "<generator>dgmd-notice-rss {}</generator>",
+        version()
+    )));
+    // The build SHA stays out of the feed.
+    assert!(!xml.contains(&format!("{}+", version())));
+}
+
+#[test]
+fn dedup_titles_collapses_reposts_in_digests_only() {
+    let mut notices = sample_notices();
+    let repost = notices[2].clone();
+    notices.insert(3, repost);
+    let title = "교내장학 신청 안내";
+
+    assert_eq!(compose("md", &notices, &[]).matches(title).count(), 2);
+    let md = compose("md", &notices, &["--dedup-titles"]);
+    assert_eq!(md.matches(title).count(), 1);
+    assert_eq!(md.matches("* **[").count(), 5);
+    let text = compose("text", &notices, &["--dedup-titles"]);
+    assert_eq!(text.matches(title).count(), 1);
+
+    let xml = compose("xml", &notices, &["--dedup-titles"]);
+    assert_eq!(xml.matches("<item>").count(), 6);
+}
+
+#[test]
+fn dates_use_each_format_s_own_representation() {
+    use crate::{format_date, DateFormat};
+    use chrono::TimeZone;
+
+    let date = chrono::Utc.with_ymd_and_hms(2025, 3, 30, 15, 0, 0).unwrap();
+    assert_eq!(
+        format_date(date, DateFormat::Rss),
+        "Sun, 30 Mar 2025 15:00:00 +0000"
+    );
+    assert_eq!(format_date(date, DateFormat::Iso), "2025-03-30T15:00:00Z");
+
+    // RSS keeps RFC 2822 while the JSON flavors carry RFC 3339.
+    let notices = sample_notices();
+    let json = compose("json", &notices, &[]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let iso = json[3]["iso_expired_at"].as_str().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(iso).is_ok(), "{}", iso);
+    let feed = compose("jsonfeed", &notices, &[]);
+    let feed = serde_json::from_str::<serde_json::Value>(&feed).unwrap();
+    let iso = feed["items"][3]["_iso_expired_at"].as_str().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(iso).is_ok(), "{}", iso);
+}
+
+#[test]
+fn romanize_appends_to_digest_titles_only() {
+    let notices = vec![super::notice(1221, "원제")];
+
+    let md = compose("md", &notices, &["--romanize"]);
+    assert!(md.contains("원제 (Wonje)"), "{}", md);
+    let text = compose("text", &notices, &["--romanize"]);
+    assert!(text.contains("원제 (Wonje)"), "{}", text);
+    let xml = compose("xml", &notices, &["--romanize"]);
+    assert!(xml.contains("<title>원제</title>"), "{}", xml);
+    assert!(!compose("md", &notices, &[]).contains("Wonje"));
+}
+
+#[test]
+fn html_mode_is_a_valid_page_with_one_item_per_notice() {
+    use scraper::{Html, Selector};
+
+    let mut notices = sample_notices();
+    notices[4].author = "<script>alert(1)</script>".to_string();
+    let page = compose("html", &notices, &[]);
+
+    assert!(page.starts_with("<!DOCTYPE html>"), "{}", page);
+    let document = Html::parse_document(&page);
+    assert!(document.errors.is_empty(), "{:?}", document.errors);
+    let select = |selector: &str| {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let items = select("ul > li");
+    assert_eq!(items.len(), 5);
+    assert_eq!(select("li.pinned").len(), 2);
+    assert_eq!(select("li > span.category").len(), 5);
+    assert_eq!(
+        items[2]
+            .select(&Selector::parse("a").unwrap())
+            .next()
+            .unwrap()
+            .value()
+            .attr("href"),
+        Some(notices[2].link.as_str())
+    );
+    assert!(select("script").is_empty());
+    assert!(items[4].text().any(|text| text.contains("<script>")));
+}
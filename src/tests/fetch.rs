@@ -0,0 +1,606 @@
+use super::{fixture, notice, options, MockServer, Response};
+use crate::{
+    backoff_delay, fetch_all, fetch_bodies, FetchConfig, Fetched, Layout, Notice, ParseConfig,
+    LIMIT, MAX_BACKOFF, OFFSET,
+};
+use std::time::Duration;
+
+fn fetch(server: &MockServer, pages: u32, config: &FetchConfig) -> crate::error::Result<Fetched> {
+    let parse_config = ParseConfig {
+        layout: Layout::Auto,
+        untitled: "(untitled)",
+        min_rows: 0,
+    };
+    fetch_all(
+        &server.board_url(),
+        LIMIT,
+        OFFSET,
+        pages,
+        config,
+        &parse_config,
+    )
+}
+
+#[test]
+fn total_count_bounds_the_pages_fetched() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("cards.html")));
+
+    let fetched = fetch(&server, 3, &FetchConfig::default()).unwrap();
+
+    assert_eq!(fetched.notices.len(), 2);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn bodies_are_fetched_in_order_within_the_limit() {
+    let server = MockServer::start();
+    let mut notices = (0..6)
+        .map(|i| notice(1221 - i, &format!("공지 {}", i)))
+        .collect::<Vec<_>>();
+    for notice in &notices {
+        let body = format!("<div class=\"b-content-box\"><p>{}</p></div>", notice.title);
+        server.route(
+            &format!("articleNo={}", notice.article_no.as_deref().unwrap()),
+            Response::html(&body).with_delay(Duration::from_millis(50)),
+        );
+    }
+
+    fetch_bodies(
+        &mut notices,
+        &server.board_url(),
+        2,
+        &FetchConfig::default(),
+    );
+
+    for notice in &notices {
+        assert_eq!(
+            notice.body.as_deref(),
+            Some(format!("<p>{}</p>", notice.title).as_str())
+        );
+    }
+    assert_eq!(server.requests().len(), 6);
+    assert!(server.max_in_flight() <= 2, "{}", server.max_in_flight());
+}
+
+#[test]
+fn cached_bodies_are_not_fetched_again() {
+    let server = MockServer::start();
+    let mut notices = vec![
+        Notice {
+            body: Some("<p>cached</p>".to_string()),
+            ..notice(2, "cached")
+        },
+        notice(1, "fresh"),
+    ];
+    server.route(
+        "mode=view",
+        Response::html("<div class=\"b-content-box\">fresh</div>"),
+    );
+
+    fetch_bodies(
+        &mut notices,
+        &server.board_url(),
+        4,
+        &FetchConfig::default(),
+    );
+
+    assert_eq!(notices[0].body.as_deref(), Some("<p>cached</p>"));
+    assert_eq!(notices[1].body.as_deref(), Some("fresh"));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn credentials_are_sent_and_redacted() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let options = options(&[
+        "--cookie",
+        "SESSION=secret",
+        "--cookie",
+        "lang=ko",
+        "--basic-auth",
+        "me:pw",
+    ]);
+
+    fetch(&server, 1, &options.fetch).unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("cookie"), Some("SESSION=secret; lang=ko"));
+    assert_eq!(request.header("authorization"), Some("Basic bWU6cHc="));
+    let debug = format!("{:?}", options.fetch.credentials);
+    assert!(
+        !debug.contains("secret") && !debug.contains("me:pw"),
+        "{}",
+        debug
+    );
+}
+
+#[test]
+fn bodies_of_external_links_are_not_fetched() {
+    let server = MockServer::start();
+    let external = MockServer::start();
+    server.route(
+        "mode=view",
+        Response::html("<div class=\"b-content-box\">board</div>"),
+    );
+    external.route(
+        "/event",
+        Response::html("<div class=\"b-content-box\">external</div>"),
+    );
+    let mut notices = vec![
+        notice(2, "board"),
+        Notice {
+            link: external.url("/event?articleNo=5"),
+            article_no: None,
+            ..notice(1, "external")
+        },
+    ];
+    let options = options(&["--cookie", "SESSION=secret", "--basic-auth", "me:pw"]);
+
+    fetch_bodies(&mut notices, &server.board_url(), 4, &options.fetch);
+
+    assert_eq!(notices[0].body.as_deref(), Some("board"));
+    assert_eq!(notices[1].body, None);
+    assert!(external.requests().is_empty());
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(
+        server.requests()[0].header("cookie"),
+        Some("SESSION=secret")
+    );
+}
+
+#[test]
+fn a_failed_middle_page_leaves_the_others() {
+    let server = MockServer::start();
+    server.route("article.offset=0", Response::html(&fixture("board.html")));
+    server.route("article.offset=30", Response::status(500, "oops"));
+    server.route(
+        "article.offset=60",
+        Response::html(&fixture("board_page2.html")),
+    );
+
+    let fetched = fetch(&server, 3, &FetchConfig::default()).unwrap();
+
+    assert_eq!(fetched.failed_offsets, [30]);
+    let indexes = fetched
+        .notices
+        .iter()
+        .map(|notice| notice.index)
+        .collect::<Vec<_>>();
+    assert_eq!(indexes.len(), 12);
+    assert_eq!(indexes[2], 1221);
+    assert_eq!(indexes[10..], [1191, 1190]);
+}
+
+#[test]
+fn list_url_merges_with_an_existing_query() {
+    use crate::{list_url, parse_base_url};
+
+    let base_url = parse_base_url(
+        "http://media.ajou.ac.kr/media/board/notice.do?srCategoryId=12&mode=view&article.offset=90",
+    )
+    .unwrap();
+
+    let url = list_url(&base_url, 30, 60);
+
+    assert_eq!(
+        url.as_str(),
+        "http://media.ajou.ac.kr/media/board/notice.do\
+         ?srCategoryId=12&mode=list&articleLimit=30&article.offset=60"
+    );
+    assert!(!url.as_str().contains("??"));
+}
+
+#[test]
+fn list_url_percent_encodes_the_base_query() {
+    use crate::{list_url, parse_base_url};
+
+    let base_url =
+        parse_base_url("http://media.ajou.ac.kr/media/board/notice.do?srSearchVal=장학 신청")
+            .unwrap();
+
+    assert_eq!(
+        list_url(&base_url, 10, 0).query(),
+        Some("srSearchVal=%EC%9E%A5%ED%95%99+%EC%8B%A0%EC%B2%AD&mode=list&articleLimit=10&article.offset=0")
+    );
+}
+
+#[test]
+fn pages_are_decoded_with_the_declared_charset() {
+    let server = MockServer::start();
+    let board = fixture("board.html").replace("charset=\"utf-8\"", "charset=\"euc-kr\"");
+    let (bytes, _, _) = encoding_rs::EUC_KR.encode(&board);
+    server.route(
+        "mode=list",
+        Response::bytes(bytes.into_owned(), "text/html; charset=EUC-KR"),
+    );
+
+    let fetched = fetch(&server, 1, &FetchConfig::default()).unwrap();
+
+    assert_eq!(fetched.notices.len(), 10);
+    assert!(fetched
+        .notices
+        .iter()
+        .any(|notice| notice.author == "소프트웨어융합대학 교학팀"));
+}
+
+#[test]
+fn invalid_bytes_are_replaced_instead_of_failing() {
+    let server = MockServer::start();
+    let mut bytes = fixture("board.html").into_bytes();
+    let title = String::from_utf8_lossy(&bytes).find("장학").unwrap();
+    bytes[title] = 0xff;
+    server.route(
+        "mode=list",
+        Response::bytes(bytes, "text/html; charset=utf-8"),
+    );
+
+    let fetched = fetch(&server, 1, &FetchConfig::default()).unwrap();
+
+    assert_eq!(fetched.notices.len(), 10);
+    assert!(fetched
+        .notices
+        .iter()
+        .any(|notice| notice.category.contains('\u{fffd}')));
+}
+
+#[test]
+fn a_notice_about_maintenance_is_not_a_maintenance_page() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+
+    let fetched = fetch(&server, 1, &FetchConfig::default()).unwrap();
+
+    assert!(fetched
+        .notices
+        .iter()
+        .any(|notice| notice.title == "2024 학사 시스템 점검 안내"));
+}
+
+#[test]
+fn pages_without_the_board_are_errors() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("maintenance.html")));
+    let err = fetch(&server, 1, &FetchConfig::default()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "parse error: offset 0 returned a maintenance page"
+    );
+
+    server.route(
+        "mode=list",
+        Response::html("<html><body>오류</body></html>"),
+    );
+    let err = fetch(&server, 1, &FetchConfig::default()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "parse error: offset 0 did not return the notice board (error page?)"
+    );
+}
+
+#[test]
+fn error_statuses_keep_a_snippet_of_the_body() {
+    use crate::{fetch_html_with, parse_base_url};
+
+    let server = MockServer::start();
+    let body = format!("  접근 권한이 없습니다. {}", "가".repeat(300));
+    server.route("mode=list", Response::status(403, &body));
+
+    let base_url = parse_base_url(&server.board_url()).unwrap();
+    let err = fetch_html_with(&FetchConfig::default(), &base_url, LIMIT, OFFSET)
+        .err()
+        .unwrap();
+
+    assert_eq!(err.exit_code(), 8);
+    let message = err.to_string();
+    assert!(
+        message.contains("returned 403: 접근 권한이 없습니다. 가"),
+        "{}",
+        message
+    );
+    let snippet = message.split("403: ").nth(1).unwrap();
+    assert_eq!(snippet.chars().count(), 200);
+    // Client errors aren't retried.
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn gists_are_posted_with_the_feed_and_return_the_raw_url() {
+    use crate::post_gist;
+
+    let server = MockServer::start();
+    let raw_url = "https://gist.githubusercontent.com/ajou-hack/abc/raw/rss.xml";
+    server.route(
+        "/gists",
+        Response::bytes(
+            serde_json::json!({ "files": { "rss.xml": { "raw_url": raw_url } } })
+                .to_string()
+                .into_bytes(),
+            "application/json",
+        ),
+    );
+
+    let url = post_gist(&server.url(""), "ghp_secret", "<rss></rss>", "rss.xml").unwrap();
+
+    assert_eq!(url, raw_url);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/gists");
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Bearer ghp_secret")
+    );
+    let body = serde_json::from_str::<serde_json::Value>(&requests[0].body).unwrap();
+    assert_eq!(body["public"], false);
+    assert_eq!(body["files"]["rss.xml"]["content"], "<rss></rss>");
+}
+
+#[test]
+fn gist_api_errors_are_reported_without_the_token() {
+    use crate::post_gist;
+
+    let server = MockServer::start();
+    server.route(
+        "/gists",
+        Response::status(401, "{\"message\":\"Bad credentials\"}"),
+    );
+
+    let err = post_gist(&server.url("/"), "ghp_secret", "<rss></rss>", "rss.xml")
+        .err()
+        .unwrap();
+
+    assert_eq!(err.exit_code(), 8);
+    let message = err.to_string();
+    assert!(
+        message.contains("returned 401: {\"message\":\"Bad credentials\"}"),
+        "{}",
+        message
+    );
+    assert!(!message.contains("ghp_secret"));
+}
+
+#[test]
+fn fetch_html_uses_the_default_config() {
+    use crate::{fetch_html, parse_base_url};
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("cards.html")));
+
+    let html = fetch_html(&parse_base_url(&server.board_url()).unwrap(), 10, 20).unwrap();
+
+    assert!(html.contains("카드 공지"));
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .ends_with("?mode=list&articleLimit=10&article.offset=20"));
+    assert_eq!(
+        requests[0].header("user-agent"),
+        Some(FetchConfig::default().user_agent.as_str())
+    );
+}
+
+#[test]
+fn a_custom_fetch_config_is_honored() {
+    use crate::{fetch_html_with, parse_base_url};
+
+    let server = MockServer::start();
+    let base_url = parse_base_url(&server.board_url()).unwrap();
+    let config = FetchConfig {
+        timeout: Some(Duration::from_millis(200)),
+        retries: 2,
+        backoff_base: Duration::from_millis(10),
+        user_agent: "notice-mirror/1.0".to_string(),
+        ..FetchConfig::default()
+    };
+
+    server.route_sequence(
+        "mode=list",
+        vec![
+            Response::status(503, "busy"),
+            Response::status(502, "busy"),
+            Response::html(&fixture("cards.html")),
+        ],
+    );
+    assert!(fetch_html_with(&config, &base_url, LIMIT, OFFSET)
+        .unwrap()
+        .contains("카드 공지"));
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|request| request.header("user-agent") == Some("notice-mirror/1.0")));
+
+    server.route(
+        "mode=list",
+        Response::html("<html></html>").with_delay(Duration::from_millis(1000)),
+    );
+    let config = FetchConfig {
+        retries: 0,
+        ..config
+    };
+    let err = fetch_html_with(&config, &base_url, LIMIT, OFFSET)
+        .err()
+        .unwrap();
+    assert_eq!(err.exit_code(), 2, "{}", err);
+}
+
+#[test]
+fn an_empty_table_is_retried_up_to_the_cap() {
+    let server = MockServer::start();
+    server.route_sequence(
+        "mode=list",
+        vec![
+            Response::html(&fixture("empty.html")),
+            Response::html(&fixture("board.html")),
+        ],
+    );
+    let config = FetchConfig {
+        empty_retries: 2,
+        backoff_base: Duration::from_millis(10),
+        ..FetchConfig::default()
+    };
+
+    let fetched = fetch(&server, 1, &config).unwrap();
+    assert_eq!(fetched.notices.len(), 10);
+    assert_eq!(server.requests().len(), 2);
+
+    // Once the retries run out the empty page falls through as it is.
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("empty.html")));
+    let fetched = fetch(&server, 1, &config).unwrap();
+    assert!(fetched.notices.is_empty());
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap_without_overflowing() {
+    let base = Duration::from_millis(500);
+    assert_eq!(backoff_delay(base, 0), base);
+    assert_eq!(backoff_delay(base, 3), Duration::from_secs(4));
+    assert_eq!(backoff_delay(base, 7), MAX_BACKOFF);
+    // 2^40 doesn't fit in a u32.
+    assert_eq!(backoff_delay(base, 31), MAX_BACKOFF);
+    assert_eq!(backoff_delay(base, 40), MAX_BACKOFF);
+}
+
+// Hits the real board, so it only runs when asked for:
+//
+//   DGMD_LIVE_TEST=1 cargo test -- --ignored live_board
+#[test]
+#[ignore]
+fn live_board_parses_into_plausible_notices() {
+    use crate::{fetch_html, parse_base_url, parse_html, BASE_URL};
+
+    if std::env::var_os("DGMD_LIVE_TEST").is_none() {
+        eprintln!("DGMD_LIVE_TEST is not set, skipping");
+        return;
+    }
+
+    let base_url = parse_base_url(BASE_URL).unwrap();
+    let html = fetch_html(&base_url, LIMIT, OFFSET).unwrap();
+    let (notices, _) = parse_html(&html, BASE_URL, "(untitled)");
+
+    assert!(!notices.is_empty());
+    assert!(notices.iter().any(|notice| notice.index > 0));
+    for notice in &notices {
+        assert!(!notice.title.trim().is_empty(), "{:?}", notice);
+        assert!(notice.link.starts_with(BASE_URL), "{:?}", notice);
+    }
+}
+
+#[test]
+fn forced_http_versions_build_a_client() {
+    use crate::{fetch_html_with, parse_base_url, request, HttpVersion};
+    use reqwest::Method;
+
+    for version in &["auto", "1.1", "2"] {
+        let config = FetchConfig {
+            http_version: version.parse::<HttpVersion>().unwrap(),
+            ..FetchConfig::default()
+        };
+        assert!(request(Method::GET, "http://localhost/", &config).is_ok());
+    }
+    assert_eq!(
+        super::options_error(&["--http-version", "3"]).to_string(),
+        "config error: invalid --http-version '3': expected 1.1, 2 or auto"
+    );
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("cards.html")));
+    let config = FetchConfig {
+        http_version: HttpVersion::Http1,
+        ..FetchConfig::default()
+    };
+    let base_url = parse_base_url(&server.board_url()).unwrap();
+    assert!(fetch_html_with(&config, &base_url, LIMIT, OFFSET)
+        .unwrap()
+        .contains("카드 공지"));
+}
+
+#[test]
+fn an_empty_search_falls_back_to_the_plain_list() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    server.route("srSearchVal=", Response::html(&fixture("empty.html")));
+    let search_url = format!(
+        "{}?srSearchKey=title&srSearchVal=%EC%97%86%EC%9D%8C",
+        server.board_url()
+    );
+    let parse_config = ParseConfig {
+        layout: Layout::Auto,
+        untitled: "(untitled)",
+        min_rows: 0,
+    };
+    let fetch_search = |search_fallback: bool| {
+        let config = FetchConfig {
+            search_fallback,
+            ..FetchConfig::default()
+        };
+        fetch_all(&search_url, LIMIT, OFFSET, 1, &config, &parse_config).unwrap()
+    };
+
+    assert!(fetch_search(false).notices.is_empty());
+
+    let mut notices = Vec::new();
+    let logged = super::captured_logs(|| {
+        notices = fetch_search(true).notices;
+    });
+    assert_eq!(notices.len(), 10);
+    // The links lead to the board, not back into the search.
+    for notice in &notices {
+        let article_no = notice.article_no.as_deref().unwrap();
+        assert!(
+            notice.link.starts_with(&format!(
+                "{}?mode=view&articleNo={}&",
+                server.board_url(),
+                article_no
+            )),
+            "{}",
+            notice.link
+        );
+    }
+    assert!(
+        logged
+            .iter()
+            .any(|line| line.starts_with("WARN search returned no rows, falling back")),
+        "{:?}",
+        logged
+    );
+    let last = server.requests().pop().unwrap();
+    assert!(!last.path.contains("srSearch"), "{}", last.path);
+    assert!(last.path.contains("mode=list"), "{}", last.path);
+}
+
+#[test]
+fn notices_carry_the_page_and_offset_they_came_from() {
+    use super::compose;
+
+    let server = MockServer::start();
+    server.route("article.offset=0", Response::html(&fixture("board.html")));
+    server.route(
+        "article.offset=30",
+        Response::html(&fixture("board_page2.html")),
+    );
+
+    let fetched = fetch(&server, 2, &FetchConfig::default()).unwrap();
+
+    let origin = |index: i32| {
+        let notice = fetched
+            .notices
+            .iter()
+            .find(|notice| notice.index == index)
+            .unwrap();
+        (notice.page, notice.source_offset)
+    };
+    assert_eq!(origin(-1), (Some(0), Some(0)));
+    assert_eq!(origin(1221), (Some(0), Some(0)));
+    assert_eq!(origin(1191), (Some(1), Some(30)));
+
+    let json = compose("json", &fetched.notices, &[]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let last = json.as_array().unwrap().last().unwrap();
+    assert_eq!(last["source_offset"], 30);
+    assert_eq!(last["page"], 1);
+}
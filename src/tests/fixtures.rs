@@ -0,0 +1,39 @@
+// Fixture loading and list-page generation. Kept free of crate internals
+// so benches/parse.rs can include this file as well.
+
+use std::fs;
+
+/// The contents of `tests/fixtures/<name>`.
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("failed to read {}: {}", path, err))
+}
+
+/// The board fixture with its numbered rows replaced by `rows` generated
+/// ones counting down from `first`.
+pub fn list_page(first: i32, rows: i32) -> String {
+    let board = fixture("board.html");
+    let start = board.find("<tr>\n <td class=\"b-num-box\">").unwrap();
+    let end = board.find("</tbody>").unwrap();
+
+    let numbered = (0..rows)
+        .map(|i| {
+            let index = first - i;
+            format!(
+                "<tr>\n \
+                 <td class=\"b-num-box\">{index}</td>\n \
+                 <td>학사</td>\n \
+                 <td class=\"b-td-left\"><div class=\"b-title-box\">\
+                 <a href=\"?mode=view&amp;articleNo={article_no}\">공지 {index}</a></div></td>\n \
+                 <td class=\"b-no-right\"></td>\n \
+                 <td>디지털미디어학과</td>\n \
+                 <td>2025-03-31</td>\n\
+                 </tr>\n",
+                index = index,
+                article_no = 340_000 + index,
+            )
+        })
+        .collect::<String>();
+
+    format!("{}{}{}", &board[..start], numbered, &board[end..])
+}
@@ -0,0 +1,431 @@
+// Fixtures, notice builders and a canned HTTP server shared by the tests.
+
+mod cli;
+mod compose;
+mod fetch;
+mod fixtures;
+mod multi;
+mod notices;
+mod parse;
+mod scrape;
+
+pub use fixtures::{fixture, list_page};
+
+use crate::error::Error;
+use crate::{compose_output, composer, parse_args, Notice, Options, BASE_URL};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::Duration;
+
+/// Options as parsed from a command line, without the program name.
+pub fn options(args: &[&str]) -> Options {
+    let args = ["dgmd-notice-rss"]
+        .iter()
+        .chain(args)
+        .map(|arg| arg.to_string());
+    parse_args(args).unwrap()
+}
+
+/// The error parsing a command line fails with.
+pub fn options_error(args: &[&str]) -> Error {
+    let argv = ["dgmd-notice-rss"]
+        .iter()
+        .chain(args)
+        .map(|arg| arg.to_string());
+    match parse_args(argv) {
+        Ok(_) => panic!("{:?} parsed", args),
+        Err(err) => err,
+    }
+}
+
+/// A numbered notice with every optional field left empty.
+pub fn notice(index: i32, title: &str) -> Notice {
+    let article_no = (340_000 + index).to_string();
+    Notice {
+        index,
+        title: title.to_string(),
+        author: "디지털미디어학과".to_string(),
+        category: "학사".to_string(),
+        link: format!("{}?mode=view&articleNo={}", BASE_URL, article_no),
+        expired_at: "2025-03-31".to_string(),
+        is_new: false,
+        is_important: false,
+        has_attachment: false,
+        body: None,
+        article_no: Some(article_no),
+        updated: false,
+        first_seen: None,
+        related_links: Vec::new(),
+        raw_html: None,
+        priority: None,
+        source_offset: None,
+        page: None,
+        scraped_hash: None,
+    }
+}
+
+/// A pinned notice, which the board shows without a number.
+pub fn pinned(priority: u32, title: &str) -> Notice {
+    let article_no = (330_000 + priority).to_string();
+    Notice {
+        index: -1,
+        is_important: true,
+        link: format!("{}?mode=view&articleNo={}", BASE_URL, article_no),
+        article_no: Some(article_no),
+        priority: Some(priority),
+        ..notice(-1, title)
+    }
+}
+
+/// Two pinned notices followed by three numbered ones across categories.
+pub fn sample_notices() -> Vec<Notice> {
+    vec![
+        Notice {
+            category: "기타".to_string(),
+            ..pinned(1, "[공지] 학과사무실 운영 시간 안내")
+        },
+        pinned(2, "2025학년도 1학기 수강신청 안내"),
+        Notice {
+            category: "장학".to_string(),
+            author: "소프트웨어융합대학 교학팀".to_string(),
+            is_new: true,
+            has_attachment: true,
+            ..notice(1221, "2025학년도 1학기 교내장학 신청 안내 & 서류 제출")
+        },
+        Notice {
+            category: "취업".to_string(),
+            expired_at: "2025-12-30".to_string(),
+            ..notice(1220, "디지털미디어학과 Career Consultant 취업/진로상담")
+        },
+        Notice {
+            category: "행사".to_string(),
+            expired_at: "2025-03-14".to_string(),
+            ..notice(1219, "2025 디지털미디어 졸업작품전시회 개최")
+        },
+    ]
+}
+
+/// A request as the mock server received it. Header names are lowercased.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Duration,
+}
+
+impl Response {
+    pub fn html(body: &str) -> Self {
+        Response {
+            status: 200,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "text/html; charset=utf-8".to_string(),
+            )],
+            body: body.as_bytes().to_vec(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn bytes(body: Vec<u8>, content_type: &str) -> Self {
+        Response {
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body,
+            ..Self::html("")
+        }
+    }
+
+    pub fn status(status: u16, body: &str) -> Self {
+        Response {
+            status,
+            ..Self::html(body)
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+struct Route {
+    pattern: String,
+    responses: VecDeque<Response>,
+}
+
+#[derive(Default)]
+struct Shared {
+    routes: Vec<Route>,
+    requests: Vec<Request>,
+    in_flight: usize,
+    max_in_flight: usize,
+}
+
+/// A local HTTP/1.1 server answering from canned responses.
+///
+/// A request is answered by the most recently added route whose pattern is a
+/// substring of its path and query. A route given several responses hands
+/// them out in order and then keeps repeating the last one; requests nothing
+/// matches get a 404.
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let server_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&server_shared);
+                thread::spawn(move || serve(stream, &shared));
+            }
+        });
+
+        MockServer { addr, shared }
+    }
+
+    pub fn route(&self, pattern: &str, response: Response) -> &Self {
+        self.route_sequence(pattern, vec![response])
+    }
+
+    /// Serves `responses` in order for `pattern`, repeating the last one.
+    pub fn route_sequence(&self, pattern: &str, responses: Vec<Response>) -> &Self {
+        self.shared.lock().unwrap().routes.push(Route {
+            pattern: pattern.to_string(),
+            responses: responses.into(),
+        });
+        self
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// The board's list endpoint on this server.
+    pub fn board_url(&self) -> String {
+        self.url("/media/board/notice.do")
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.shared.lock().unwrap().requests.clone()
+    }
+
+    /// The most requests that were being answered at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.shared.lock().unwrap().max_in_flight
+    }
+}
+
+fn serve(stream: TcpStream, shared: &Mutex<Shared>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+        return;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let response = {
+        let mut shared = shared.lock().unwrap();
+        let response = shared
+            .routes
+            .iter_mut()
+            .rev()
+            .find(|route| path.contains(&route.pattern))
+            .map(|route| match route.responses.len() {
+                1 => route.responses[0].clone(),
+                _ => route.responses.pop_front().unwrap(),
+            })
+            .unwrap_or_else(|| Response::status(404, "not found"));
+        shared.requests.push(Request {
+            method: method.clone(),
+            path,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+        shared.in_flight += 1;
+        shared.max_in_flight = shared.max_in_flight.max(shared.in_flight);
+        response
+    };
+    thread::sleep(response.delay);
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let mut stream = stream;
+    let _ = stream.write_all(head.as_bytes());
+    if method != "HEAD" {
+        let _ = stream.write_all(&response.body);
+    }
+    let _ = stream.flush();
+    shared.lock().unwrap().in_flight -= 1;
+}
+
+/// The output of `mode` for `notices`, composed the way scrape would with
+/// the options from `args`.
+pub fn compose(mode: &str, notices: &[Notice], args: &[&str]) -> String {
+    let options = options(args);
+    let composer = composer(mode, &options, None, 0).unwrap();
+    compose_output(composer.as_ref(), notices, &options).unwrap()
+}
+
+/// Panics unless `xml` is well-formed: one root, every tag closed in order,
+/// CDATA sections and comments closed, and no stray `&` or `<` in text.
+pub fn assert_well_formed(xml: &str) {
+    let mut open = Vec::new();
+    let mut roots = 0;
+    let mut rest = xml.trim_start();
+    if rest.starts_with("<?xml") {
+        rest = &rest[rest.find("?>").expect("unterminated declaration") + 2..];
+    }
+
+    while let Some(start) = rest.find(['<', '&']) {
+        assert_text(&rest[..start], !open.is_empty(), xml);
+        rest = &rest[start..];
+        if rest.starts_with('&') {
+            let end = rest.find(';').expect("unterminated entity");
+            let entity = &rest[1..end];
+            assert!(
+                ["amp", "lt", "gt", "quot", "apos"].contains(&entity) || entity.starts_with('#'),
+                "unknown entity &{}; in {}",
+                entity,
+                xml
+            );
+            rest = &rest[end + 1..];
+        } else if rest.starts_with("<![CDATA[") {
+            assert!(!open.is_empty(), "CDATA outside the root in {}", xml);
+            let end = rest.find("]]>").expect("unterminated CDATA");
+            rest = &rest[end + 3..];
+        } else if rest.starts_with("<!--") {
+            let end = rest.find("-->").expect("unterminated comment");
+            rest = &rest[end + 3..];
+        } else {
+            let end = rest.find('>').expect("unterminated tag");
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(
+                    open.pop(),
+                    Some(name.trim()),
+                    "mismatched </{}> in {}",
+                    name,
+                    xml
+                );
+                continue;
+            }
+            if open.is_empty() {
+                roots += 1;
+            }
+            if !tag.ends_with('/') {
+                open.push(tag.split_whitespace().next().expect("empty tag"));
+            }
+        }
+    }
+
+    assert_text(rest, false, xml);
+    assert!(open.is_empty(), "unclosed {:?} in {}", open, xml);
+    assert_eq!(roots, 1, "expected a single root in {}", xml);
+}
+
+fn assert_text(text: &str, in_root: bool, xml: &str) {
+    assert!(!text.contains("]]>"), "stray ]]> in {}", xml);
+    assert!(
+        in_root || text.trim().is_empty(),
+        "text outside the root in {}",
+        xml
+    );
+}
+
+thread_local! {
+    static LOGGED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Keeps the records logged on the capturing thread, so concurrent tests
+/// don't see each other's output.
+struct CapturingLogger;
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        LOGGED.with(|logged| {
+            if let Some(logged) = logged.borrow_mut().as_mut() {
+                logged.push(format!("{} {}", record.level(), record.args()));
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Runs `f`, returning what it logged as "LEVEL message" lines.
+pub fn captured_logs(f: impl FnOnce()) -> Vec<String> {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&CapturingLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+    });
+
+    LOGGED.with(|logged| *logged.borrow_mut() = Some(Vec::new()));
+    f();
+    LOGGED.with(|logged| logged.borrow_mut().take().unwrap())
+}
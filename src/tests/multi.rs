@@ -0,0 +1,127 @@
+use super::{fixture, options, MockServer, Response};
+use crate::run_multi;
+use crate::state::load_state;
+use std::fs;
+use std::path::Path;
+
+fn config(server: &MockServer, dir: &Path) -> String {
+    let boards = ["media", "software"]
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "base_url": server.url(&format!("/{}/board/notice.do", id)),
+                "title": format!("{} notices", id),
+                "home_page_url": format!("https://{}.ajou.ac.kr/", id),
+                "feed_url": format!("https://example.com/{}.xml", id),
+            })
+        })
+        .collect::<Vec<_>>();
+    let path = dir.join("boards.json");
+    fs::write(&path, serde_json::json!({ "boards": boards }).to_string()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn multi_args<'a>(config: &'a str, dir: &'a str, state: &'a str) -> Vec<&'a str> {
+    vec![
+        "--config",
+        config,
+        "--output-dir",
+        dir,
+        "--state",
+        state,
+        "multi",
+    ]
+}
+
+#[test]
+fn multi_writes_a_feed_and_state_entry_per_board() {
+    let server = MockServer::start();
+    server.route("/media/", Response::html(&fixture("board.html")));
+    server.route("/software/", Response::html(&fixture("no_tbody.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(&server, dir.path());
+    let state = dir.path().join("state.json");
+    let out = dir.path().to_str().unwrap();
+
+    run_multi(&options(&multi_args(&config, out, state.to_str().unwrap()))).unwrap();
+
+    let media = fs::read_to_string(dir.path().join("media.xml")).unwrap();
+    let software = fs::read_to_string(dir.path().join("software.xml")).unwrap();
+    assert!(media.contains("<title>media notices</title>"));
+    assert_eq!(media.matches("<item>").count(), 9);
+    assert!(software.contains("tbody 없는 공지"));
+    assert!(dir.path().join("feeds.opml").exists());
+
+    let state = load_state(state.to_str().unwrap()).unwrap();
+    assert_eq!(state.boards["media"].last_index, 1221);
+    assert_eq!(state.boards["software"].last_index, 7);
+}
+
+#[test]
+fn multi_writes_nothing_when_a_board_fails() {
+    let server = MockServer::start();
+    server.route("/media/", Response::html(&fixture("board.html")));
+    server.route("/software/", Response::html(&fixture("maintenance.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(&server, dir.path());
+    let state = dir.path().join("state.json");
+    let out = dir.path().to_str().unwrap();
+
+    assert!(run_multi(&options(&multi_args(&config, out, state.to_str().unwrap()))).is_err());
+
+    assert!(!dir.path().join("media.xml").exists());
+    assert!(!dir.path().join("feeds.opml").exists());
+    assert!(!state.exists());
+}
+
+#[test]
+fn multi_rejects_single_board_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut args = multi_args("boards.json", dir.path().to_str().unwrap(), "state.json");
+    args.splice(0..0, ["--only-new", "--top", "5"]);
+
+    let err = run_multi(&options(&args)).unwrap_err();
+
+    assert_eq!(err.exit_code(), 6);
+    assert_eq!(
+        err.to_string(),
+        "config error: multi mode does not support --only-new, --top"
+    );
+}
+
+#[test]
+fn the_max_items_flag_wins_over_the_board_config() {
+    let server = MockServer::start();
+    server.route("/media/", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("boards.json");
+    let board = serde_json::json!({
+        "id": "media",
+        "base_url": server.url("/media/board/notice.do"),
+        "title": "media notices",
+        "home_page_url": "https://media.ajou.ac.kr/",
+        "feed_url": "https://example.com/media.xml",
+        "max_items": 3,
+    });
+    fs::write(
+        &config,
+        serde_json::json!({ "boards": [board] }).to_string(),
+    )
+    .unwrap();
+    let config = config.to_str().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let items = |extra: &[&str], state: &str| {
+        let state = dir.path().join(state);
+        let mut args = multi_args(config, out, state.to_str().unwrap());
+        args.splice(0..0, extra.iter().copied());
+        run_multi(&options(&args)).unwrap();
+        fs::read_to_string(dir.path().join("media.xml"))
+            .unwrap()
+            .matches("<item>")
+            .count()
+    };
+
+    assert_eq!(items(&[], "first.json"), 3);
+    assert_eq!(items(&["--max-items", "5"], "second.json"), 5);
+}
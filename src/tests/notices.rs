@@ -0,0 +1,145 @@
+use super::{compose, notice, options_error, pinned, sample_notices};
+use crate::{dedup_notices, notice_id, sort_notices, Notice};
+
+fn titles(notices: &[Notice]) -> Vec<&str> {
+    notices.iter().map(|notice| notice.title.as_str()).collect()
+}
+
+#[test]
+fn sort_breaks_index_ties_by_article_then_title() {
+    let with_article = |article_no: &str, title: &str| Notice {
+        article_no: Some(article_no.to_string()),
+        link: format!("?articleNo={}", article_no),
+        ..notice(100, title)
+    };
+    let notices = vec![
+        with_article("340002", "가"),
+        with_article("340001", "하"),
+        notice(101, "최신"),
+        with_article("340001", "나"),
+        pinned(2, "고정 2"),
+        pinned(1, "고정 1"),
+    ];
+
+    let mut sorted = notices.clone();
+    sort_notices(&mut sorted);
+    assert_eq!(
+        titles(&sorted),
+        ["고정 1", "고정 2", "최신", "나", "하", "가"]
+    );
+
+    let mut reversed = notices.into_iter().rev().collect::<Vec<_>>();
+    sort_notices(&mut reversed);
+    assert_eq!(titles(&reversed), titles(&sorted));
+}
+
+#[test]
+fn sort_keeps_sorted_notices_as_they_are() {
+    let mut notices = sample_notices();
+    sort_notices(&mut notices);
+    assert_eq!(titles(&notices), titles(&sample_notices()));
+}
+
+#[test]
+fn article_no_is_the_identity_for_dedup_and_guids() {
+    let mut moved = pinned(1, "2025학년도 1학기 수강신청 안내");
+    moved.article_no = Some("340010".to_string());
+    moved.link = "?mode=view&articleNo=340010&article.offset=30".to_string();
+    let numbered = Notice {
+        article_no: Some("340010".to_string()),
+        link: "?mode=view&articleNo=340010&article.offset=0".to_string(),
+        ..notice(1218, "2025학년도 1학기 수강신청 안내")
+    };
+    let mut notices = vec![moved, numbered];
+
+    dedup_notices(&mut notices, false);
+
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].index, 1218);
+    assert_eq!(notice_id(&notices[0]), "340010");
+    assert!(compose("xml", &notices, &[]).contains("<guid isPermaLink=\"false\">340010</guid>"));
+}
+
+#[test]
+fn link_is_the_identity_without_an_article_no() {
+    let notice = Notice {
+        article_no: None,
+        link: "https://www.ajou.ac.kr/event".to_string(),
+        ..notice(5, "외부 공지")
+    };
+    assert_eq!(notice_id(&notice), "https://www.ajou.ac.kr/event");
+}
+
+#[test]
+fn filter_regex_matches_the_title_or_the_body() {
+    use crate::matches_regex;
+    use regex::Regex;
+
+    let regex = Regex::new("장학|채용").unwrap();
+    let notices = sample_notices();
+    assert!(matches_regex(&notices[2], &regex));
+    assert!(!matches_regex(&notices[3], &regex));
+
+    let with_body = Notice {
+        body: Some("<p>채용 연계형 인턴십</p>".to_string()),
+        ..notices[3].clone()
+    };
+    assert!(matches_regex(&with_body, &regex));
+}
+
+#[test]
+fn invalid_filter_regex_fails_at_startup() {
+    let err = options_error(&["--filter-regex", "(장학"]);
+
+    assert_eq!(err.exit_code(), 6);
+    assert!(err
+        .to_string()
+        .starts_with("config error: invalid --filter-regex '(장학'"));
+}
+
+#[test]
+fn view_url_uses_the_article_number() {
+    let notice = Notice {
+        article_no: Some("340001".to_string()),
+        link: "?mode=view&articleNo=340001&article.offset=0&articleLimit=30".to_string(),
+        ..notice(1221, "2025학년도 1학기 교내장학 신청 안내")
+    };
+    assert_eq!(
+        notice.view_url("http://media.ajou.ac.kr/media/board/notice.do"),
+        "http://media.ajou.ac.kr/media/board/notice.do?mode=view&articleNo=340001"
+    );
+
+    let without_article = Notice {
+        article_no: None,
+        link: "https://www.ajou.ac.kr/event".to_string(),
+        ..pinned(1, "교외 행사 안내")
+    };
+    assert_eq!(
+        without_article.view_url("http://media.ajou.ac.kr/media/board/notice.do"),
+        "https://www.ajou.ac.kr/event"
+    );
+}
+
+#[test]
+fn pinned_duplicates_are_dropped_unless_kept() {
+    let (notices, _) =
+        crate::parse_html(&super::fixture("board.html"), crate::BASE_URL, "(untitled)");
+    let copies = |notices: &[Notice]| {
+        notices
+            .iter()
+            .filter(|notice| notice.article_no.as_deref() == Some("340010"))
+            .map(|notice| notice.index)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(copies(&notices), [-1, 1218]);
+
+    let mut deduped = notices.clone();
+    dedup_notices(&mut deduped, false);
+    assert_eq!(copies(&deduped), [1218]);
+    assert_eq!(deduped.len(), 9);
+
+    let mut kept = notices;
+    dedup_notices(&mut kept, true);
+    assert_eq!(copies(&kept), [-1, 1218]);
+    assert_eq!(kept.len(), 10);
+}
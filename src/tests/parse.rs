@@ -0,0 +1,488 @@
+use super::fixture;
+use crate::{
+    debug_dump, parse_attr, parse_html, parse_page, parse_total_count, Layout, ParseConfig,
+    BASE_URL,
+};
+use scraper::{Html, Selector};
+
+const UNTITLED: &str = "(untitled)";
+
+fn config(layout: Layout) -> ParseConfig<'static> {
+    ParseConfig {
+        layout,
+        untitled: UNTITLED,
+        min_rows: 0,
+    }
+}
+
+#[test]
+fn board_fixture_parses_every_row() {
+    let (notices, row_errors) = parse_html(&fixture("board.html"), BASE_URL, UNTITLED);
+
+    assert!(row_errors.is_empty(), "{:?}", row_errors);
+    assert_eq!(notices.len(), 10);
+    assert_eq!(
+        notices
+            .iter()
+            .map(|notice| notice.index)
+            .collect::<Vec<_>>(),
+        [-1, -1, 1221, 1220, 1219, 1218, 1217, 1216, 1215, 1214]
+    );
+    assert_eq!(
+        notices
+            .iter()
+            .map(|notice| notice.category.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "기타", "학사", "장학", "취업", "행사", "학사", "장학", "취업", "학사", "기타"
+        ]
+    );
+
+    let first = &notices[2];
+    assert_eq!(
+        first.title,
+        "2025학년도 1학기 교내장학 신청 안내 & 서류 제출"
+    );
+    assert_eq!(first.author, "소프트웨어융합대학 교학팀");
+    assert_eq!(first.expired_at, "2025-03-31");
+    assert_eq!(
+        first.link,
+        format!(
+            "{}?mode=view&articleNo=340001&article.offset=0&articleLimit=30",
+            BASE_URL
+        )
+    );
+    assert_eq!(first.article_no.as_deref(), Some("340001"));
+}
+
+#[test]
+fn cards_fixture_parses_with_auto_layout() {
+    let (notices, _) = parse_page(&fixture("cards.html"), BASE_URL, &config(Layout::Auto));
+
+    assert_eq!(notices.len(), 2);
+    assert_eq!(notices[0].index, -1);
+    assert_eq!(notices[1].index, 12);
+    assert_eq!(notices[1].title, "카드 공지");
+}
+
+#[test]
+fn an_explicit_layout_does_not_fall_back() {
+    let (notices, _) = parse_page(&fixture("cards.html"), BASE_URL, &config(Layout::Cards));
+    assert_eq!(notices.len(), 2);
+    let (notices, _) = parse_page(&fixture("cards.html"), BASE_URL, &config(Layout::Table));
+    assert!(notices.is_empty());
+    let (notices, _) = parse_page(&fixture("board.html"), BASE_URL, &config(Layout::Cards));
+    assert!(notices.is_empty());
+}
+
+fn attr(html: &str, selector: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    parse_attr(
+        &fragment.root_element(),
+        &Selector::parse(selector).unwrap(),
+    )
+}
+
+#[test]
+fn parse_attr_returns_the_href() {
+    assert_eq!(
+        attr(
+            r#"<td><a href="?mode=view&amp;articleNo=1">title</a></td>"#,
+            "a"
+        ),
+        "?mode=view&articleNo=1"
+    );
+}
+
+#[test]
+fn parse_attr_without_href_is_empty() {
+    assert_eq!(attr("<td><a>title</a></td>", "a"), "");
+    assert_eq!(attr("<td>no anchor</td>", "a"), "");
+}
+
+#[test]
+fn parse_attr_takes_the_first_href() {
+    let html = r#"<td><a>no link</a><a href="first">one</a><a href="second">two</a></td>"#;
+    assert_eq!(attr(html, "a"), "first");
+}
+
+fn table(rows: &str) -> String {
+    format!(
+        "<table class=\"board-table\"><tbody>{}</tbody></table>",
+        rows
+    )
+}
+
+fn row(index: &str, title: &str, href: &str) -> String {
+    format!(
+        "<tr><td class=\"b-num-box\">{}</td><td>학사</td>\
+         <td class=\"b-td-left\"><div class=\"b-title-box\"><a href=\"{}\">{}</a></div></td>\
+         <td class=\"b-no-right\"></td><td>디지털미디어학과</td><td>2025-03-31</td></tr>",
+        index, href, title
+    )
+}
+
+#[test]
+fn broken_rows_are_skipped_and_reported() {
+    let broken = "<tr><td class=\"b-num-box\">2</td><td>학사</td>\
+                  <td class=\"b-td-left\"><div class=\"b-title-box\"></div></td></tr>";
+    let html = table(&format!(
+        "{}{}",
+        row("3", "정상 공지", "?articleNo=3"),
+        broken
+    ));
+
+    let (notices, row_errors) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].title, "정상 공지");
+    assert_eq!(row_errors, ["row 2: missing link"]);
+}
+
+#[test]
+fn total_count_is_read_from_the_label() {
+    assert_eq!(parse_total_count(&fixture("board.html")), Some(1221));
+    assert_eq!(parse_total_count("<p>총 1,221 건</p>"), Some(1221));
+    assert_eq!(parse_total_count(&fixture("no_tbody.html")), None);
+}
+
+#[test]
+fn badges_are_parsed_from_the_row_markup() {
+    let (notices, _) = parse_html(&fixture("board.html"), BASE_URL, UNTITLED);
+    let flags = |index: i32| {
+        let notice = notices.iter().find(|notice| notice.index == index).unwrap();
+        (notice.is_new, notice.is_important, notice.has_attachment)
+    };
+
+    assert_eq!(flags(1221), (true, false, true));
+    assert_eq!(flags(1220), (true, false, false));
+    assert_eq!(flags(1219), (false, false, false));
+    assert_eq!(flags(1218), (false, false, true));
+    assert!(notices[0].is_important && notices[1].is_important);
+}
+
+#[test]
+fn important_badge_marks_numbered_rows() {
+    let html = table(&row(
+        "5",
+        "중요 공지 <img src=\"/img/icon_important.gif\" alt=\"중요\">",
+        "?articleNo=5",
+    ));
+    let (notices, _) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert_eq!(notices[0].index, 5);
+    assert!(notices[0].is_important);
+    assert!(!notices[0].is_new && !notices[0].has_attachment);
+}
+
+#[test]
+fn article_no_is_read_from_the_href() {
+    use crate::extract_article_no;
+
+    assert_eq!(
+        extract_article_no("?mode=view&articleNo=340001&article.offset=0&articleLimit=30")
+            .as_deref(),
+        Some("340001")
+    );
+    assert_eq!(
+        extract_article_no("notice.do?articleNo=7").as_deref(),
+        Some("7")
+    );
+    assert_eq!(extract_article_no("?mode=view&articleNo="), None);
+    assert_eq!(extract_article_no("?mode=view&article.offset=0"), None);
+    assert_eq!(extract_article_no("notice.do"), None);
+}
+
+#[test]
+fn rows_without_a_title_get_the_placeholder() {
+    use super::compose;
+
+    let html = table(&format!(
+        "{}{}",
+        row("3", " \n\t ", "?mode=view&amp;articleNo=340003"),
+        row("2", "정상 공지", "?mode=view&amp;articleNo=340002")
+    ));
+
+    let (notices, row_errors) = parse_html(&html, BASE_URL, "(제목 없음)");
+
+    assert_eq!(notices.len(), 2);
+    assert_eq!(notices[0].title, "(제목 없음)");
+    assert_eq!(row_errors, ["row 1: missing title"]);
+    let xml = compose("xml", &notices, &[]);
+    assert!(xml.contains("<title>(제목 없음)</title>"), "{}", xml);
+    assert!(!xml.contains("<title></title>"));
+}
+
+#[test]
+fn tables_without_tbody_still_parse_their_rows() {
+    let (notices, row_errors) = parse_html(&fixture("no_tbody.html"), BASE_URL, UNTITLED);
+
+    assert!(row_errors.is_empty(), "{:?}", row_errors);
+    let rows = notices
+        .iter()
+        .map(|notice| {
+            (
+                notice.index,
+                notice.title.as_str(),
+                notice.category.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [(7, "tbody 없는 공지", "학사"), (6, "두 번째 공지", "장학")]
+    );
+}
+
+#[test]
+fn header_rows_in_thead_are_not_notices() {
+    let html = format!(
+        "<table class=\"board-table\"><thead><tr><th>번호</th><th>제목</th></tr></thead>{}</table>",
+        row("5", "thead 아래 공지", "?mode=view&amp;articleNo=50")
+    );
+
+    let (notices, row_errors) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert!(row_errors.is_empty(), "{:?}", row_errors);
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].title, "thead 아래 공지");
+}
+
+#[test]
+fn title_selectors_fall_back_when_the_markup_shifts() {
+    let cell = |title_cell: &str| {
+        format!(
+            "<tr><td class=\"b-num-box\">9</td><td>학사</td>{}\
+             <td class=\"b-no-right\"></td><td>디지털미디어학과</td><td>2025-03-31</td></tr>",
+            title_cell
+        )
+    };
+    let html = table(&format!(
+        "{}{}",
+        // The title box became a span.
+        cell(
+            "<td class=\"b-td-left\"><span class=\"b-title-box\">\
+              <a href=\"?mode=view&amp;articleNo=90\">span 제목</a></span></td>"
+        ),
+        // The cell lost its class.
+        cell("<td><a href=\"?mode=view&amp;articleNo=91\">클래스 없는 제목</a></td>")
+    ));
+
+    let (notices, row_errors) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert!(row_errors.is_empty(), "{:?}", row_errors);
+    let titles = notices
+        .iter()
+        .map(|notice| (notice.title.as_str(), notice.article_no.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        [("span 제목", Some("90")), ("클래스 없는 제목", Some("91")),]
+    );
+}
+
+#[test]
+fn debug_dump_prints_each_parsed_notice() {
+    let (notices, _) = parse_html(&fixture("board.html"), BASE_URL, UNTITLED);
+
+    let dump = debug_dump(&notices);
+    let lines = dump.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), notices.len());
+    assert!(lines[0].starts_with("Notice { index: -1,"), "{}", lines[0]);
+    let scholarship = lines
+        .iter()
+        .find(|line| line.contains("index: 1221,"))
+        .unwrap();
+    for field in &[
+        "title: \"2025학년도 1학기 교내장학 신청 안내 & 서류 제출\"",
+        "category: \"장학\"",
+        "is_new: true",
+        "article_no: Some(\"340001\")",
+    ] {
+        assert!(scholarship.contains(field), "{} in {}", field, scholarship);
+    }
+}
+
+#[test]
+fn an_unknown_layout_is_a_config_error() {
+    assert_eq!(
+        super::options_error(&["--layout", "grid"]).to_string(),
+        "config error: invalid --layout 'grid': expected table, cards or auto"
+    );
+}
+
+#[test]
+fn the_index_is_read_past_icons_and_wrappers() {
+    let html = table(&format!(
+        "{}{}",
+        row(
+            "<span class=\"b-icon\"><img src=\"/img/icon_hot.gif\" alt=\"인기\">HOT</span> <span>1207</span>",
+            "아이콘 붙은 번호",
+            "?articleNo=1207",
+        ),
+        row("\n  <em>1206</em>\n", "감싼 번호", "?articleNo=1206"),
+    ));
+
+    let (notices, row_errors) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert!(row_errors.is_empty(), "{:?}", row_errors);
+    assert_eq!(
+        notices
+            .iter()
+            .map(|notice| notice.index)
+            .collect::<Vec<_>>(),
+        [1207, 1206]
+    );
+}
+
+#[test]
+fn the_title_anchor_is_the_primary_link() {
+    let html = table(
+        "<tr><td class=\"b-num-box\">9</td>\
+         <td><a href=\"?mode=download&amp;attachNo=1\">첨부</a></td>\
+         <td class=\"b-td-left\"><div class=\"b-title-box\">\
+         <a href=\"?mode=view&amp;articleNo=340009\">여러 링크 공지</a></div>\
+         <a href=\"?mode=view&amp;articleNo=340009\" target=\"_blank\">새 창</a>\
+         <a href=\"#\">맨 위로</a>\
+         <a href=\"https://example.com/form\">신청서</a></td>\
+         <td>디지털미디어학과</td><td>2025-03-31</td></tr>",
+    );
+
+    let (notices, _) = parse_html(&html, BASE_URL, UNTITLED);
+
+    let notice = &notices[0];
+    assert_eq!(
+        notice.link,
+        format!("{}?mode=view&articleNo=340009", BASE_URL)
+    );
+    assert_eq!(notice.article_no.as_deref(), Some("340009"));
+    assert_eq!(
+        notice.related_links,
+        [
+            format!("{}?mode=download&attachNo=1", BASE_URL),
+            "https://example.com/form".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn raw_html_is_in_the_json_only_with_include_raw_html() {
+    use super::compose;
+
+    let (notices, _) = parse_html(&fixture("board.html"), BASE_URL, UNTITLED);
+    let scholarship = notices
+        .iter()
+        .position(|notice| notice.index == 1221)
+        .unwrap();
+
+    let json = compose("json", &notices, &[]);
+    let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert!(parsed[scholarship].get("raw_html").is_none());
+    assert!(!json.contains("raw_html"));
+
+    let json = compose("json", &notices, &["--include-raw-html"]);
+    let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let raw_html = parsed[scholarship]["raw_html"].as_str().unwrap();
+    assert!(raw_html.starts_with("<tr>"), "{}", raw_html);
+    assert!(
+        raw_html.contains("<td class=\"b-num-box\">1221</td>"),
+        "{}",
+        raw_html
+    );
+    assert!(raw_html.contains("articleNo=340001"), "{}", raw_html);
+}
+
+fn pinned_row(title: &str, article_no: u32) -> String {
+    format!(
+        "<tr class=\"b-top-box\"><td class=\"b-num-box\"><span class=\"b-notice\">공지</span></td>\
+         <td>기타</td><td class=\"b-td-left\"><div class=\"b-title-box\">\
+         <a href=\"?mode=view&amp;articleNo={}\">{}</a></div></td>\
+         <td class=\"b-no-right\"></td><td>디지털미디어학과</td><td>2025-01-14</td></tr>",
+        article_no, title
+    )
+}
+
+#[test]
+fn pinned_rows_keep_the_board_order_as_priority() {
+    use super::compose;
+
+    let html = table(&format!(
+        "{}{}{}{}",
+        pinned_row("첫 고정", 337990),
+        pinned_row("둘째 고정", 337980),
+        pinned_row("셋째 고정", 337995),
+        row("12", "일반 공지", "?articleNo=12"),
+    ));
+
+    let (notices, _) = parse_html(&html, BASE_URL, UNTITLED);
+
+    let priorities = notices
+        .iter()
+        .map(|notice| (notice.title.as_str(), notice.priority))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        priorities,
+        [
+            ("첫 고정", Some(1)),
+            ("둘째 고정", Some(2)),
+            ("셋째 고정", Some(3)),
+            ("일반 공지", None),
+        ]
+    );
+    let json = compose("json", &notices, &[]);
+    let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(json[2]["priority"], 3);
+    assert!(json[3].get("priority").is_none());
+}
+
+#[test]
+fn article_no_is_only_taken_from_links_to_the_board() {
+    let html = table(&format!(
+        "{}{}{}",
+        row("3", "외부 공지", "https://example.com/event?articleNo=5"),
+        row(
+            "2",
+            "절대 경로 공지",
+            &format!("{}?mode=view&amp;articleNo=340002", BASE_URL),
+        ),
+        row("1", "상대 경로 공지", "?mode=view&amp;articleNo=340001"),
+    ));
+
+    let (notices, _) = parse_html(&html, BASE_URL, UNTITLED);
+
+    assert_eq!(notices[0].link, "https://example.com/event?articleNo=5");
+    assert_eq!(notices[0].article_no, None);
+    assert_eq!(notices[0].view_url(BASE_URL), notices[0].link);
+    assert_eq!(notices[1].article_no.as_deref(), Some("340002"));
+    assert_eq!(notices[2].article_no.as_deref(), Some("340001"));
+}
+
+#[test]
+fn links_resolve_against_a_base_url_with_a_query_string() {
+    let base_url = "http://media.ajou.ac.kr/media/board/notice.do?lang=ko";
+    let html = table(&format!(
+        "{}{}",
+        row("2", "상대 경로 공지", "?mode=view&amp;articleNo=340002"),
+        row(
+            "1",
+            "루트 경로 공지",
+            "/media/board/notice.do?mode=view&amp;articleNo=340001"
+        ),
+    ));
+
+    let (notices, _) = parse_html(&html, base_url, UNTITLED);
+
+    assert_eq!(
+        notices[0].link,
+        "http://media.ajou.ac.kr/media/board/notice.do?mode=view&articleNo=340002"
+    );
+    assert_eq!(
+        notices[1].link,
+        "http://media.ajou.ac.kr/media/board/notice.do?mode=view&articleNo=340001"
+    );
+    assert_eq!(
+        notices[0].view_url(base_url),
+        "http://media.ajou.ac.kr/media/board/notice.do?lang=ko&mode=view&articleNo=340002"
+    );
+    assert_eq!(notices[1].article_no.as_deref(), Some("340001"));
+}
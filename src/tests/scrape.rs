@@ -0,0 +1,1462 @@
+use super::{fixture, list_page, options, MockServer, Response};
+use crate::error::Error;
+use crate::state::load_state;
+use crate::state::State;
+use crate::{post_webhook, scrape, Credentials, FetchConfig, BOARD_ID};
+use std::fs;
+
+#[test]
+fn scrape_writes_a_feed_from_the_board() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    let latest_index = scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    assert_eq!(latest_index, 1221);
+    let xml = fs::read_to_string(output).unwrap();
+    assert_eq!(xml.matches("<item>").count(), 9);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert!(requests[0].path.contains("article.offset=0"));
+    assert!(requests[0]
+        .header("user-agent")
+        .unwrap()
+        .contains(env!("CARGO_PKG_NAME")));
+}
+
+#[test]
+fn fail_on_empty_errors_without_writing() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("empty.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--fail-on-empty",
+    ]);
+
+    let err = scrape(
+        &options,
+        "xml",
+        1200,
+        state_path.to_str(),
+        &mut State::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.exit_code(), 4);
+    assert!(!output.exists());
+    assert!(!state_path.exists());
+}
+
+#[test]
+fn empty_board_without_fail_on_empty_exits_with_no_notices() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("empty.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    let err = scrape(
+        &options,
+        "xml",
+        1200,
+        state_path.to_str(),
+        &mut State::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, Error::NoNotices(0)), "{}", err);
+    assert_eq!(err.exit_code(), 10);
+    assert!(!output.exists());
+    assert!(!state_path.exists());
+}
+
+#[test]
+fn top_keeps_the_newest_numbered_notices_and_the_pinned_ones() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&list_page(1221, 30)));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--top",
+        "5",
+    ]);
+
+    scrape(&options, "json", 0, None, &mut State::default()).unwrap();
+
+    let json = fs::read_to_string(output).unwrap();
+    let notices = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+    let indexes = notices
+        .iter()
+        .map(|notice| notice["index"].as_i64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(indexes, [-1, -1, 1221, 1220, 1219, 1218, 1217]);
+}
+
+#[test]
+fn max_items_caps_the_feed_after_top() {
+    let board = list_page(1221, 30);
+
+    // Pinned notices count towards --max-items but not towards --top.
+    assert_eq!(scraped_titles(&board, &["--max-items", "4"]).len(), 4);
+    assert_eq!(
+        scraped_titles(&board, &["--top", "5", "--max-items", "4"]).len(),
+        4
+    );
+    assert_eq!(
+        scraped_titles(&board, &["--top", "2", "--max-items", "10"]).len(),
+        4
+    );
+}
+
+#[test]
+fn backfill_seeds_the_state_and_the_next_run_finds_nothing_new() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let state_path = state_path.to_str();
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    let mut state = State::default();
+    assert_eq!(
+        scrape(&options, "backfill", 0, state_path, &mut state).unwrap(),
+        1221
+    );
+    assert!(!output.exists());
+    let saved = load_state(state_path.unwrap()).unwrap();
+    let board_state = &saved.boards[BOARD_ID];
+    assert_eq!(board_state.last_index, 1221);
+    assert_eq!(board_state.seen.len(), 9);
+    assert!(board_state.seen.contains(&"340001".to_string()));
+
+    let mut state = saved;
+    assert_eq!(
+        scrape(&options, "xml", 1221, state_path, &mut state).unwrap(),
+        1221
+    );
+    assert!(!output.exists());
+}
+
+#[test]
+fn an_edited_deadline_is_emitted_again() {
+    let server = MockServer::start();
+    let board = fixture("board.html");
+    server.route("mode=list", Response::html(&board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.md");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+    let mut state = State::default();
+    scrape(&options, "md", 0, None, &mut state).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    // Nothing changed: no output.
+    scrape(&options, "md", 1221, None, &mut state).unwrap();
+    assert!(!output.exists());
+
+    server.route(
+        "mode=list",
+        Response::html(&board.replace("2025-12-30", "2026-01-15")),
+    );
+    scrape(&options, "md", 1221, None, &mut state).unwrap();
+
+    let md = fs::read_to_string(&output).unwrap();
+    assert!(md.contains("[수정] 디지털미디어학과 Career Consultant 취업/진로상담"));
+    assert!(md.contains("~2026-01-15"));
+    assert_eq!(md.matches("[수정]").count(), 1);
+}
+
+#[test]
+fn turning_on_decoding_and_normalizing_is_not_an_edit() {
+    let server = MockServer::start();
+    let board = fixture("board.html").replace(
+        "2025 디지털미디어 졸업작품전시회 개최",
+        "2025%20%EC%A1%B8%EC%97%85%EC%9E%91%ED%92%88%EC%A0%84%EC%8B%9C%ED%9A%8C\u{FF01}",
+    );
+    server.route("mode=list", Response::html(&board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.md");
+    let base_url = server.board_url();
+    let args = [
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+    ];
+    let mut state = State::default();
+    scrape(&options(&args), "md", 0, None, &mut state).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    // The titles come out differently, but the board itself didn't change.
+    let mut args = args.to_vec();
+    args.extend_from_slice(&["--percent-decode", "--normalize"]);
+    scrape(&options(&args), "md", 1221, None, &mut state).unwrap();
+    assert!(!output.exists());
+}
+
+#[test]
+fn partial_results_need_allow_partial() {
+    let server = MockServer::start();
+    server.route("article.offset=0", Response::html(&fixture("board.html")));
+    server.route("article.offset=30", Response::status(503, "busy"));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let args = [
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--pages",
+        "2",
+    ];
+
+    let err = scrape(&options(&args), "xml", 0, None, &mut State::default()).unwrap_err();
+    assert_eq!(err.exit_code(), 3);
+    assert!(!output.exists());
+
+    let mut args = args.to_vec();
+    args.push("--allow-partial");
+    scrape(&options(&args), "xml", 0, None, &mut State::default()).unwrap();
+    assert!(output.exists());
+}
+
+#[test]
+fn only_new_skips_notices_already_seen() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--only-new",
+    ]);
+    // 1221 was recorded under another number before it was reposted.
+    let mut state = State::default();
+    state
+        .boards
+        .entry(BOARD_ID.to_string())
+        .or_default()
+        .record(
+            1219,
+            vec![("340001".to_string(), "old".to_string())].into_iter(),
+            500,
+        );
+
+    scrape(&options, "json", 1219, None, &mut state).unwrap();
+
+    let json = fs::read_to_string(output).unwrap();
+    let notices = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0]["index"], 1220);
+}
+
+#[test]
+fn only_new_leaves_everything_alone_when_just_an_edit_came_in() {
+    let server = MockServer::start();
+    let board = fixture("board.html");
+    server.route("mode=list", Response::html(&board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let state_path = dir.path().join("state.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--only-new",
+    ]);
+    let mut state = State::default();
+    scrape(&options, "json", 0, state_path.to_str(), &mut state).unwrap();
+    fs::remove_file(&output).unwrap();
+    let saved = fs::read_to_string(&state_path).unwrap();
+
+    server.route(
+        "mode=list",
+        Response::html(&board.replace("2025-12-30", "2026-01-15")),
+    );
+    assert_eq!(
+        scrape(&options, "json", 1221, state_path.to_str(), &mut state).unwrap(),
+        1221
+    );
+
+    assert!(!output.exists());
+    assert_eq!(fs::read_to_string(&state_path).unwrap(), saved);
+}
+
+fn scraped_body(extra: &[&str]) -> String {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    server.route("mode=view", Response::html(&fixture("detail.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let base_url = server.board_url();
+    let mut args = vec![
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--with-body",
+    ];
+    args.extend_from_slice(extra);
+
+    scrape(&options(&args), "json", 0, None, &mut State::default()).unwrap();
+
+    let json = fs::read_to_string(output).unwrap();
+    let notices = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+    notices[0]["body"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn bodies_are_sanitized_unless_raw_body() {
+    let body = scraped_body(&[]);
+    assert!(!body.contains("<script>"), "{}", body);
+    assert!(!body.contains("onclick"), "{}", body);
+    assert!(body.contains("<strong>아래와 같이</strong>"), "{}", body);
+    assert!(body.contains("<a href=\"https://www.ajou.ac.kr/scholarship.pdf\""));
+
+    let raw = scraped_body(&["--raw-body"]);
+    assert!(raw.contains("<script>alert(1)</script>"), "{}", raw);
+    assert!(raw.contains("onclick=\"track()\""), "{}", raw);
+}
+
+#[test]
+fn preview_chars_keeps_a_plain_text_prefix() {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let preview = scraped_body(&["--preview-chars", "20"]);
+
+    assert_eq!(preview, "2025학년도 1학기 교내장학 신청을…");
+    assert_eq!(preview.graphemes(true).count(), 21);
+    assert!(!preview.contains('<'), "{}", preview);
+
+    // Long enough for the whole text: no ellipsis, and no script either.
+    let preview = scraped_body(&["--preview-chars", "500"]);
+    assert!(preview.ends_with("제출 서류: 신청서"), "{}", preview);
+    assert!(!preview.contains("alert"), "{}", preview);
+}
+
+#[test]
+fn watch_iterations_only_write_when_the_board_changes() {
+    use crate::watch_iteration;
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&list_page(1221, 5)));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let state_path = state_path.to_str();
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--interval-secs",
+        "1",
+    ]);
+    let mut state = State::default();
+
+    let last_index = watch_iteration(&options, "xml", 0, state_path, &mut state);
+    assert_eq!(last_index, 1221);
+    assert!(output.exists());
+    fs::remove_file(&output).unwrap();
+
+    let last_index = watch_iteration(&options, "xml", last_index, state_path, &mut state);
+    assert_eq!(last_index, 1221);
+    assert!(!output.exists());
+
+    server.route("mode=list", Response::html(&list_page(1222, 6)));
+    let last_index = watch_iteration(&options, "xml", last_index, state_path, &mut state);
+    assert_eq!(last_index, 1222);
+    let xml = fs::read_to_string(&output).unwrap();
+    assert!(xml.contains("<title>공지 1222</title>"), "{}", xml);
+    assert_eq!(
+        load_state(state_path.unwrap()).unwrap().boards[BOARD_ID].last_index,
+        1222
+    );
+
+    // A failed scrape keeps the index for the next iteration.
+    server.route("mode=list", Response::status(500, "error"));
+    assert_eq!(
+        watch_iteration(&options, "xml", last_index, state_path, &mut state),
+        1222
+    );
+}
+
+fn scraped_titles(board: &str, extra: &[&str]) -> Vec<String> {
+    scraped_field(board, extra, "title")
+}
+
+/// `field` of every notice a json scrape of `board` emits.
+fn scraped_field(board: &str, extra: &[&str], field: &str) -> Vec<String> {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let base_url = server.board_url();
+    let mut args = vec![
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+    ];
+    args.extend_from_slice(extra);
+
+    scrape(&options(&args), "json", 0, None, &mut State::default()).unwrap();
+
+    let json = fs::read_to_string(output).unwrap();
+    serde_json::from_str::<Vec<serde_json::Value>>(&json)
+        .unwrap()
+        .iter()
+        .map(|notice| notice[field].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn percent_decode_only_decodes_when_asked() {
+    let board = fixture("board.html")
+        .replace(
+            "2025 디지털미디어 졸업작품전시회 개최",
+            "2025%20%EC%A1%B8%EC%97%85%EC%9E%91%ED%92%88%EC%A0%84%EC%8B%9C%ED%9A%8C",
+        )
+        .replace(
+            "학과 사물함 배정 결과 안내",
+            "사물함 보증금 100% 환급 (%25 표기)",
+        );
+
+    let decoded = scraped_titles(&board, &["--percent-decode"]);
+    assert!(
+        decoded.contains(&"2025 졸업작품전시회".to_string()),
+        "{:?}",
+        decoded
+    );
+    // A literal '%' isn't a valid escape, and "%25" is decoded only once.
+    assert!(decoded.contains(&"사물함 보증금 100% 환급 (% 표기)".to_string()));
+
+    let raw = scraped_titles(&board, &[]);
+    assert!(raw.contains(
+        &"2025%20%EC%A1%B8%EC%97%85%EC%9E%91%ED%92%88%EC%A0%84%EC%8B%9C%ED%9A%8C".to_string()
+    ));
+    assert!(raw.contains(&"사물함 보증금 100% 환급 (%25 표기)".to_string()));
+}
+
+fn scrape_board(board: &str, extra: &[&str]) -> (crate::error::Result<i32>, bool, bool) {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let base_url = server.board_url();
+    let mut args = vec![
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+    ];
+    args.extend_from_slice(extra);
+
+    let result = scrape(
+        &options(&args),
+        "xml",
+        0,
+        state_path.to_str(),
+        &mut State::default(),
+    );
+    (result, output.exists(), state_path.exists())
+}
+
+#[test]
+fn a_maintenance_page_aborts_without_writing() {
+    let (result, wrote_output, wrote_state) = scrape_board(&fixture("maintenance.html"), &[]);
+
+    assert_eq!(result.err().unwrap().exit_code(), 4);
+    assert!(!wrote_output);
+    assert!(!wrote_state);
+}
+
+#[test]
+fn min_rows_rejects_a_short_first_page() {
+    let (result, wrote_output, wrote_state) =
+        scrape_board(&fixture("board.html"), &["--min-rows", "11"]);
+    let err = result.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "parse error: offset 0 has 10 rows, fewer than --min-rows 11"
+    );
+    assert!(!wrote_output);
+    assert!(!wrote_state);
+
+    let (result, wrote_output, _) = scrape_board(&fixture("board.html"), &["--min-rows", "10"]);
+    assert_eq!(result.unwrap(), 1221);
+    assert!(wrote_output);
+}
+
+fn dc_date<'a>(xml: &'a str, title: &str) -> &'a str {
+    let item = xml
+        .split("<item>")
+        .find(|item| item.contains(&format!("<title>{}</title>", title)))
+        .unwrap_or_else(|| panic!("no item '{}' in {}", title, xml));
+    let start = item.find("<dc:date>").unwrap() + "<dc:date>".len();
+    let end = item.find("</dc:date>").unwrap();
+    &item[start..end]
+}
+
+#[test]
+fn first_seen_is_kept_across_runs() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&list_page(1222, 2)));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let state_path = state_path.to_str();
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--emit-first-seen",
+    ]);
+    let mut state = State::default();
+    state
+        .boards
+        .entry(BOARD_ID.to_string())
+        .or_default()
+        .first_seen("341221".to_string(), "2025-03-01T00:00:00Z");
+
+    scrape(&options, "xml", 1221, state_path, &mut state).unwrap();
+
+    let xml = fs::read_to_string(&output).unwrap();
+    assert_eq!(dc_date(&xml, "공지 1221"), "2025-03-01T00:00:00Z");
+    let first_seen = dc_date(&xml, "공지 1222").to_string();
+    assert!(
+        first_seen.as_str() > "2025-03-01T00:00:00Z",
+        "{}",
+        first_seen
+    );
+
+    server.route("mode=list", Response::html(&list_page(1223, 3)));
+    let mut state = load_state(state_path.unwrap()).unwrap();
+    scrape(&options, "xml", 1222, state_path, &mut state).unwrap();
+
+    let xml = fs::read_to_string(&output).unwrap();
+    assert_eq!(dc_date(&xml, "공지 1222"), first_seen);
+    assert_eq!(dc_date(&xml, "공지 1221"), "2025-03-01T00:00:00Z");
+}
+
+#[test]
+fn normalize_folds_fullwidth_punctuation_and_spaces() {
+    let board = fixture("board.html").replace(
+        "학과 사물함 배정 결과 안내",
+        "학과\u{3000}사물함 배정（결과）안내！ Ｗｅｂ",
+    );
+
+    let normalized = scraped_titles(&board, &["--normalize"]);
+    // Hangul and fullwidth letters are left alone; only the punctuation and
+    // the ideographic space are folded.
+    assert!(
+        normalized.contains(&"학과 사물함 배정(결과)안내! Ｗｅｂ".to_string()),
+        "{:?}",
+        normalized
+    );
+
+    let raw = scraped_titles(&board, &[]);
+    assert!(raw.contains(&"학과\u{3000}사물함 배정（결과）안내！ Ｗｅｂ".to_string()));
+}
+
+#[test]
+fn normalize_composes_decomposed_hangul() {
+    let decomposed = "\u{1112}\u{1161}\u{11A8}\u{1109}\u{1161} 일정";
+    let board = fixture("board.html").replace("학과 사물함 배정 결과 안내", decomposed);
+
+    let normalized = scraped_titles(&board, &["--normalize"]);
+    assert!(
+        normalized.contains(&"학사 일정".to_string()),
+        "{:?}",
+        normalized
+    );
+}
+
+#[test]
+fn only_new_emits_just_the_notices_after_last_index() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let base_url = server.board_url();
+    let args = [
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+    ];
+
+    scrape(&options(&args), "xml", 1219, None, &mut State::default()).unwrap();
+    assert_eq!(
+        fs::read_to_string(&output)
+            .unwrap()
+            .matches("<item>")
+            .count(),
+        9
+    );
+
+    let mut only_new = args.to_vec();
+    only_new.push("--only-new");
+    scrape(
+        &options(&only_new),
+        "xml",
+        1219,
+        None,
+        &mut State::default(),
+    )
+    .unwrap();
+    let xml = fs::read_to_string(&output).unwrap();
+    assert_eq!(xml.matches("<item>").count(), 2, "{}", xml);
+    assert!(xml.contains("<guid isPermaLink=\"false\">340001</guid>"));
+    assert!(xml.contains("<guid isPermaLink=\"false\">339999</guid>"));
+
+    let md = dir.path().join("notices.md");
+    only_new[3] = md.to_str().unwrap();
+    scrape(&options(&only_new), "md", 1219, None, &mut State::default()).unwrap();
+    let md = fs::read_to_string(&md).unwrap();
+    assert!(md.contains("Career Consultant"), "{}", md);
+    assert!(!md.contains("졸업작품전시회"), "{}", md);
+    assert!(!md.contains("스케치 노트"), "{}", md);
+}
+
+#[test]
+fn link_rewrites_apply_before_encoding() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let base_url = server.board_url();
+    let host = server.url("");
+    let options = options(&[
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--link-replace",
+        &host,
+        "https://media.ajou.ac.kr",
+        "--link-prefix",
+        "https://r.example.com/?to=",
+    ]);
+
+    scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    let xml = fs::read_to_string(output).unwrap();
+    assert!(
+        xml.contains(
+            "<link>https://r.example.com/?to=https://media.ajou.ac.kr/media/board/notice.do\
+         ?mode=view&amp;articleNo=340001&amp;article.offset=0&amp;articleLimit=30</link>"
+        ),
+        "{}",
+        xml
+    );
+    assert!(!xml.contains(&format!("<link>{}", host)));
+}
+
+#[test]
+fn strict_refuses_a_board_with_a_malformed_row() {
+    let board = fixture("board.html").replace(
+        "<td class=\"b-num-box\">1219</td>",
+        "<td class=\"b-num-box\"></td>",
+    );
+
+    let (result, wrote_output, wrote_state) = scrape_board(&board, &["--strict"]);
+    let err = result.err().unwrap();
+    assert_eq!(err.exit_code(), 4);
+    assert_eq!(
+        err.to_string(),
+        "parse error: 1 malformed rows in strict mode:\noffset 0, row 5: missing index"
+    );
+    assert!(!wrote_output);
+    assert!(!wrote_state);
+
+    // Without --strict the row is kept, unnumbered like a pinned one.
+    let (result, wrote_output, _) = scrape_board(&board, &[]);
+    assert_eq!(result.unwrap(), 1221);
+    assert!(wrote_output);
+}
+
+#[test]
+fn split_by_category_writes_a_feed_per_category() {
+    let server = MockServer::start();
+    let board = fixture("board.html").replace("<td>행사</td>", "<td></td>");
+    server.route("mode=list", Response::html(&board));
+    let dir = tempfile::tempdir().unwrap();
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--split-by-category",
+        "--output-dir",
+        dir.path().to_str().unwrap(),
+    ]);
+
+    scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    let mut feeds = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let items = fs::read_to_string(&path).unwrap().matches("<item>").count();
+            (
+                path.file_name().unwrap().to_str().unwrap().to_string(),
+                items,
+            )
+        })
+        .collect::<Vec<_>>();
+    feeds.sort();
+    let feeds = feeds
+        .iter()
+        .map(|(name, items)| (name.as_str(), *items))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        feeds,
+        [
+            ("misc.xml", 1),
+            ("기타.xml", 2),
+            ("장학.xml", 2),
+            ("취업.xml", 2),
+            ("학사.xml", 2),
+        ]
+    );
+}
+
+#[test]
+fn max_description_len_cuts_long_bodies_on_grapheme_boundaries() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let body = format!(
+        "<div class=\"b-content-box\"><p>{}👨‍👩‍👧 가족 &amp; 친구 초청 행사</p></div>",
+        "가".repeat(8)
+    );
+    server.route("mode=view", Response::html(&body));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--full",
+        "--max-description-len",
+        "9",
+    ]);
+
+    scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    let xml = fs::read_to_string(output).unwrap();
+    super::assert_well_formed(&xml);
+    assert!(
+        xml.contains(&format!(
+            "<description><![CDATA[{}👨‍👩‍👧… <a href=\"{}?mode=view&amp;articleNo=340001",
+            "가".repeat(8),
+            server.board_url()
+        )),
+        "{}",
+        xml
+    );
+    assert!(xml.contains("\">더보기</a>]]></description>"), "{}", xml);
+    assert!(!xml.contains("초청"));
+}
+
+#[test]
+fn short_bodies_are_left_whole() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    server.route("mode=view", Response::html(&fixture("detail.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--full",
+        "--max-description-len",
+        "1000",
+    ]);
+
+    scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    let xml = fs::read_to_string(output).unwrap();
+    assert!(xml.contains("<strong>아래와 같이</strong>"), "{}", xml);
+    assert!(!xml.contains("더보기"));
+}
+
+#[test]
+fn an_unchanged_last_modified_skips_the_run() {
+    let server = MockServer::start();
+    let board = |last_modified: &str| {
+        Response::html(&fixture("board.html")).with_header("Last-Modified", last_modified)
+    };
+    server.route("mode=list", board("Mon, 03 Mar 2025 00:00:00 GMT"));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let state_path = state_path.to_str();
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--check-last-modified",
+    ]);
+    let methods = || {
+        server
+            .requests()
+            .into_iter()
+            .map(|request| request.method)
+            .collect::<Vec<_>>()
+    };
+
+    let mut state = State::default();
+    assert_eq!(
+        scrape(&options, "xml", 0, state_path, &mut state).unwrap(),
+        1221
+    );
+    assert_eq!(methods(), ["HEAD", "GET"]);
+    let saved = load_state(state_path.unwrap()).unwrap();
+    assert_eq!(
+        saved.boards[BOARD_ID].last_modified.as_deref(),
+        Some("Mon, 03 Mar 2025 00:00:00 GMT")
+    );
+    fs::remove_file(&output).unwrap();
+
+    // Even with an older last_index, nothing is fetched or written.
+    let mut state = saved;
+    assert_eq!(
+        scrape(&options, "xml", 1200, state_path, &mut state).unwrap(),
+        1200
+    );
+    assert_eq!(methods(), ["HEAD", "GET", "HEAD"]);
+    assert!(!output.exists());
+
+    server.route("mode=list", board("Tue, 04 Mar 2025 00:00:00 GMT"));
+    assert_eq!(
+        scrape(&options, "xml", 1200, state_path, &mut state).unwrap(),
+        1221
+    );
+    assert_eq!(methods(), ["HEAD", "GET", "HEAD", "HEAD", "GET"]);
+    assert!(output.exists());
+}
+
+#[test]
+fn a_page_past_the_end_with_only_pinned_rows_writes_nothing() {
+    // Past the last page the board still shows its pinned notices.
+    let (result, wrote_output, wrote_state) = scrape_board(&list_page(0, 0), &[]);
+
+    assert!(matches!(result, Err(Error::NoNotices(0))), "{:?}", result);
+    assert!(!wrote_output);
+    assert!(!wrote_state);
+}
+
+#[test]
+fn an_empty_page_after_the_first_is_not_an_error() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&list_page(0, 0)));
+    server.route("article.offset=0", Response::html(&list_page(30, 30)));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--pages",
+        "3",
+    ]);
+
+    assert_eq!(
+        scrape(&options, "xml", 0, None, &mut State::default()).unwrap(),
+        30
+    );
+    // 30 numbered rows and one pinned; the other pinned row is number 10.
+    let xml = fs::read_to_string(output).unwrap();
+    assert_eq!(xml.matches("<item>").count(), 31);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn errors_are_posted_to_the_error_webhook() {
+    use crate::run_reporting_errors;
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("maintenance.html")));
+    server.route("/hooks/error", Response::status(204, ""));
+    let hook = server.url("/hooks/error");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--error-webhook",
+        &hook,
+        "1200",
+    ]);
+
+    let err = run_reporting_errors(&options).err().unwrap();
+
+    assert_eq!(err.exit_code(), 4);
+    let requests = server.requests();
+    let posted = requests
+        .iter()
+        .find(|request| request.path == "/hooks/error")
+        .unwrap();
+    assert_eq!(posted.method, "POST");
+    let payload = serde_json::from_str::<serde_json::Value>(&posted.body).unwrap();
+    assert_eq!(
+        payload["error"],
+        "parse error: offset 0 returned a maintenance page"
+    );
+    assert_eq!(payload["exit_code"], 4);
+}
+
+#[test]
+fn a_failing_error_webhook_keeps_the_original_error() {
+    use crate::run_reporting_errors;
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("maintenance.html")));
+    server.route("/hooks/error", Response::status(500, "down"));
+    let hook = server.url("/hooks/error");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--error-webhook",
+        &hook,
+        "1200",
+    ]);
+
+    let err = run_reporting_errors(&options).err().unwrap();
+
+    assert_eq!(err.exit_code(), 4);
+    assert_eq!(
+        err.to_string(),
+        "parse error: offset 0 returned a maintenance page"
+    );
+}
+
+#[test]
+fn emit_checksum_writes_a_sha256sum_sidecar() {
+    use sha2::{Digest, Sha256};
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--emit-checksum",
+    ]);
+
+    scrape(&options, "xml", 0, None, &mut State::default()).unwrap();
+
+    let digest = Sha256::digest(fs::read(&output).unwrap())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    let sidecar = fs::read_to_string(dir.path().join("rss.xml.sha256")).unwrap();
+    assert_eq!(sidecar, format!("{}  rss.xml\n", digest));
+}
+
+#[test]
+fn the_checksum_of_known_bytes_is_stable() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("feed.xml");
+
+    crate::write_output(path.to_str().unwrap(), b"abc", true).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("feed.xml.sha256")).unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  feed.xml\n"
+    );
+}
+
+#[test]
+fn stopping_watch_between_iterations_leaves_a_valid_state() {
+    use crate::watch;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rss.xml");
+    let state_path = dir.path().join("state.json");
+    let options = options(&[
+        "--base-url",
+        &server.board_url(),
+        "--output",
+        output.to_str().unwrap(),
+        "--state",
+        state_path.to_str().unwrap(),
+        "--interval-secs",
+        "60",
+        "watch",
+        "xml",
+    ]);
+    let stopped = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let watcher = scope.spawn(|| watch(&options, &stopped));
+        // Stop once the first iteration is done and watch is waiting.
+        while !state_path.exists() {
+            thread::sleep(Duration::from_millis(20));
+        }
+        stopped.store(true, Ordering::SeqCst);
+        watcher.join().unwrap().unwrap();
+    });
+
+    let state = load_state(state_path.to_str().unwrap()).unwrap();
+    assert_eq!(state.boards[BOARD_ID].last_index, 1221);
+    assert!(!dir.path().join("state.json.tmp").exists());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn new_by_badge_emits_only_badged_rows_without_a_last_index() {
+    let board = fixture("board.html");
+
+    let titles = scraped_titles(&board, &["--new-by-badge"]);
+    assert_eq!(
+        titles,
+        [
+            "2025학년도 1학기 교내장학 신청 안내 & 서류 제출",
+            "디지털미디어학과 Career Consultant 취업/진로상담",
+        ]
+    );
+
+    // A known last index takes over from the badge.
+    assert_eq!(scraped_titles(&board, &["--new-by-badge", "1200"]).len(), 9);
+}
+
+#[test]
+fn an_extra_notice_goes_on_top_of_the_feed() {
+    let dir = tempfile::tempdir().unwrap();
+    let extra = dir.path().join("extra.json");
+    fs::write(
+        &extra,
+        r#"[{"title": "디지털미디어 외부 특강", "link": "https://example.com/talk", "category": "행사"}]"#,
+    )
+    .unwrap();
+
+    let titles = scraped_titles(
+        &fixture("board.html"),
+        &["--extra-notice-file", extra.to_str().unwrap()],
+    );
+    assert_eq!(titles.len(), 10);
+    assert_eq!(titles[0], "디지털미디어 외부 특강");
+
+    fs::write(
+        &extra,
+        r#"[{"title": "외부 특강", "url": "https://example.com"}]"#,
+    )
+    .unwrap();
+    let err = super::options_error(&["--extra-notice-file", extra.to_str().unwrap()]);
+    assert_eq!(err.exit_code(), 6);
+    assert!(err.to_string().contains("unknown field `url`"), "{}", err);
+}
+
+#[test]
+fn post_webhook_sends_the_composed_output() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    server.route_sequence(
+        "/hooks/feed",
+        vec![Response::status(503, "busy"), Response::status(204, "")],
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let base_url = server.board_url();
+    let hook = server.url("/hooks/feed");
+    let options = options(&[
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--post-webhook",
+        &hook,
+        "--retries",
+        "1",
+        "--backoff-ms",
+        "10",
+    ]);
+
+    scrape(&options, "json", 1219, None, &mut State::default()).unwrap();
+
+    let posts = server
+        .requests()
+        .into_iter()
+        .filter(|request| request.path == "/hooks/feed")
+        .collect::<Vec<_>>();
+    assert_eq!(posts.len(), 2);
+    let posted = &posts[1];
+    assert_eq!(posted.method, "POST");
+    assert_eq!(
+        posted.header("content-type"),
+        Some("application/json; charset=utf-8")
+    );
+    assert_eq!(posted.body, fs::read_to_string(&output).unwrap());
+
+    // Nothing new, nothing posted.
+    scrape(&options, "json", 1221, None, &mut State::default()).unwrap();
+    let posts = server
+        .requests()
+        .iter()
+        .filter(|request| request.path == "/hooks/feed")
+        .count();
+    assert_eq!(posts, 2);
+}
+
+#[test]
+fn post_webhook_skips_the_board_proxy_and_credentials() {
+    let hook = MockServer::start();
+    hook.route("/hooks/feed", Response::status(204, ""));
+    let proxy = MockServer::start();
+    proxy.route("/", Response::status(502, "proxied"));
+    let config = FetchConfig {
+        proxy: Some(proxy.url("")),
+        credentials: Credentials {
+            cookies: vec!["JSESSIONID=board".to_string()],
+            basic_auth: Some("user:secret".to_string()),
+        },
+        ..FetchConfig::default()
+    };
+
+    post_webhook(&hook.url("/hooks/feed"), "{}", "json", &config).unwrap();
+
+    assert!(proxy.requests().is_empty());
+    let posted = &hook.requests()[0];
+    assert_eq!(posted.header("cookie"), None);
+    assert_eq!(posted.header("authorization"), None);
+}
+
+#[test]
+fn mapped_authors_replace_the_scraped_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let map = dir.path().join("authors.toml");
+    fs::write(
+        &map,
+        "\"소프트웨어융합대학 교학팀\" = \"소프트웨어융합대학\"\n\"디지털미디어학과 김조교\" = \"디지털미디어학과\"\n",
+    )
+    .unwrap();
+
+    let authors = scraped_field(
+        &fixture("board.html"),
+        &["--map-author-file", map.to_str().unwrap()],
+        "author",
+    );
+
+    assert!(authors.contains(&"소프트웨어융합대학".to_string()));
+    assert!(!authors
+        .iter()
+        .any(|author| author.contains("교학팀") || author.contains("김조교")));
+    // Unmapped authors pass through unchanged.
+    assert!(authors.contains(&"학생지원팀".to_string()));
+}
+
+#[test]
+fn editing_the_author_map_does_not_mark_notices_updated() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let map = dir.path().join("authors.toml");
+    let output = dir.path().join("notices.json");
+    let base_url = server.board_url();
+    let args = [
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--map-author-file",
+        map.to_str().unwrap(),
+    ];
+    let mut state = State::default();
+    fs::write(
+        &map,
+        "\"소프트웨어융합대학 교학팀\" = \"소프트웨어융합대학\"\n",
+    )
+    .unwrap();
+    scrape(&options(&args), "json", 0, None, &mut state).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    // The map is read when the options are parsed.
+    fs::write(&map, "\"소프트웨어융합대학 교학팀\" = \"SW융합대학\"\n").unwrap();
+    scrape(&options(&args), "json", 1221, None, &mut state).unwrap();
+
+    assert!(!output.exists());
+}
+
+#[test]
+fn a_run_ends_with_a_summary_line() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("feed.xml");
+    let base_url = server.board_url();
+    let options = options(&[
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    let logged = super::captured_logs(|| {
+        scrape(&options, "xml", 1219, None, &mut State::default()).unwrap();
+    });
+
+    // The pinned copy of 1218 is dropped as a duplicate.
+    let summary = logged
+        .iter()
+        .find(|line| line.starts_with("INFO run: "))
+        .unwrap_or_else(|| panic!("no summary in {:?}", logged));
+    assert!(
+        summary.starts_with("INFO run: fetched=10 new=2 pinned=1 mode=xml elapsed_ms="),
+        "{}",
+        summary
+    );
+    let elapsed = summary.rsplit('=').next().unwrap();
+    assert!(elapsed.parse::<u128>().is_ok(), "{}", summary);
+}
+
+#[test]
+fn every_way_a_run_ends_logs_one_summary() {
+    let summaries = |board: &str, last_index: i32, extra: &[&str]| {
+        let server = MockServer::start();
+        server.route("mode=list", Response::html(board));
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("feed.xml");
+        let base_url = server.board_url();
+        let mut args = vec![
+            "--base-url",
+            &base_url,
+            "--output",
+            output.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        let options = options(&args);
+        super::captured_logs(|| {
+            let _ = scrape(&options, "xml", last_index, None, &mut State::default());
+        })
+        .into_iter()
+        .filter(|line| line.starts_with("INFO run: "))
+        .collect::<Vec<_>>()
+    };
+    let board = fixture("board.html");
+
+    // Nothing new since last time.
+    let logged = summaries(&board, 1221, &[]);
+    assert_eq!(logged.len(), 1, "{:?}", logged);
+    assert!(logged[0].starts_with("INFO run: fetched=10 new=0 pinned=1 "));
+    // No numbered notices at all.
+    let logged = summaries(&list_page(0, 0), 0, &[]);
+    assert_eq!(logged.len(), 1, "{:?}", logged);
+    assert!(logged[0].starts_with("INFO run: fetched=2 new=0 pinned=0 "));
+    // A failing run, here --fail-on-empty on an empty board.
+    let logged = summaries(&fixture("empty.html"), 0, &["--fail-on-empty"]);
+    assert_eq!(logged.len(), 1, "{:?}", logged);
+    assert!(logged[0].starts_with("INFO run: fetched=0 new=0 pinned=0 "));
+}
+
+#[test]
+fn deterministic_runs_are_byte_identical() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let base_url = server.board_url();
+    let generator = format!(
+        "<generator>dgmd-notice-rss {}</generator>",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for mode in &["xml", "md"] {
+        let output = dir.path().join(format!("feed.{}", mode));
+        let options = options(&[
+            "--base-url",
+            &base_url,
+            "--output",
+            output.to_str().unwrap(),
+            "--deterministic",
+            "--relative-dates",
+        ]);
+        let run = || {
+            scrape(&options, mode, 1219, None, &mut State::default()).unwrap();
+            fs::read(&output).unwrap()
+        };
+
+        let first = run();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert_eq!(first, run(), "{} output changed", mode);
+
+        let text = String::from_utf8(first).unwrap();
+        assert!(!text.contains("lastBuildDate"));
+        // Relative deadlines are switched off, so dates stay as posted.
+        assert!(!text.contains("마감(지남)"));
+        assert_eq!(*mode == "xml", text.contains(&generator));
+    }
+}
+
+#[test]
+fn extra_notices_only_take_an_article_no_from_board_links() {
+    let server = MockServer::start();
+    server.route("mode=list", Response::html(&fixture("board.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let base_url = server.board_url();
+    let extra = dir.path().join("extra.json");
+    fs::write(
+        &extra,
+        format!(
+            r#"[
+                {{"title": "게시판 공지", "link": "{}?mode=view&articleNo=337000"}},
+                {{"title": "외부 공지", "link": "https://example.com/event?articleNo=5"}}
+            ]"#,
+            base_url
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("feed.xml");
+    let options = options(&[
+        "--base-url",
+        &base_url,
+        "--extra-notice-file",
+        extra.to_str().unwrap(),
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+
+    scrape(&options, "xml", 1219, None, &mut State::default()).unwrap();
+
+    let xml = fs::read_to_string(output).unwrap();
+    assert!(xml.contains("<guid isPermaLink=\"false\">337000</guid>"));
+    assert!(
+        xml.contains("<guid isPermaLink=\"false\">https://example.com/event?articleNo=5</guid>")
+    );
+}
+
+#[test]
+fn unchanged_notices_reuse_the_cached_body() {
+    let server = MockServer::start();
+    let board = fixture("board.html");
+    server.route("mode=list", Response::html(&board));
+    server.route("mode=view", Response::html(&fixture("detail.html")));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("notices.json");
+    let base_url = server.board_url();
+    let options = options(&[
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--with-body",
+    ]);
+    let mut state = State::default();
+    let detail_fetches = || {
+        server
+            .requests()
+            .iter()
+            .filter(|request| request.path.contains("mode=view"))
+            .count()
+    };
+
+    scrape(&options, "json", 1200, None, &mut state).unwrap();
+    let first = detail_fetches();
+    assert_eq!(first, 9);
+
+    scrape(&options, "json", 1200, None, &mut state).unwrap();
+    assert_eq!(detail_fetches(), first);
+    let json = fs::read_to_string(&output).unwrap();
+    assert!(json.contains("아래와 같이"), "{}", json);
+
+    // An edited row invalidates just its own cached body.
+    server.route(
+        "mode=list",
+        Response::html(&board.replace("취업/진로상담", "취업/진로상담 (일정 변경)")),
+    );
+    scrape(&options, "json", 1200, None, &mut state).unwrap();
+    assert_eq!(detail_fetches(), first + 1);
+}
+
+#[test]
+fn a_changed_layout_trips_abort_on_layout_change() {
+    let server = MockServer::start();
+    let board = fixture("board.html");
+    server.route("mode=list", Response::html(&board));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("feed.xml");
+    let base_url = server.board_url();
+    let args = [
+        "--base-url",
+        &base_url,
+        "--output",
+        output.to_str().unwrap(),
+        "--abort-on-layout-change",
+    ];
+    let mut state = State::default();
+
+    scrape(&options(&args), "xml", 1219, None, &mut state).unwrap();
+    let fingerprint = state.boards[BOARD_ID].layout_fingerprint.clone().unwrap();
+    assert!(fingerprint.contains("td.b-num-box"), "{}", fingerprint);
+    let written = fs::read(&output).unwrap();
+
+    server.route(
+        "mode=list",
+        Response::html(&board.replace("b-no-right", "b-file-cell")),
+    );
+    let err = scrape(&options(&args), "xml", 1200, None, &mut state)
+        .err()
+        .unwrap();
+    assert_eq!(err.exit_code(), 12, "{}", err);
+    assert!(err.to_string().contains("td.b-file-cell"), "{}", err);
+    assert_eq!(fs::read(&output).unwrap(), written);
+    assert_eq!(state.boards[BOARD_ID].layout_fingerprint, Some(fingerprint));
+
+    // Without the flag the change is only logged and then accepted.
+    scrape(&options(&args[..4]), "xml", 1200, None, &mut state).unwrap();
+    assert!(state.boards[BOARD_ID]
+        .layout_fingerprint
+        .as_deref()
+        .unwrap()
+        .contains("td.b-file-cell"));
+}